@@ -0,0 +1,314 @@
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use solcat_client::names::{listing_status_name, parse_listing_status, parse_risk_type};
+use solcat_client::pda;
+use solcat_client::solcat_program::calculate_risk_score;
+use solcat_client::solcat_program::state::{ListingStatus, ReportHistory, RiskAssessment, RiskMetrics};
+use solcat_indexer::{IndexerError, Result, Row, SqlValue, Store};
+
+pub const MAX_TOP: u32 = 100;
+
+// Values the indexer never writes are errors, not zeros or empty strings
+fn int(row: &Row, index: usize) -> Result<i64> {
+    row[index].as_i64().ok_or(IndexerError::UnexpectedValue(index))
+}
+
+fn text(row: &Row, index: usize) -> Result<&str> {
+    row[index].as_str().ok_or(IndexerError::UnexpectedValue(index))
+}
+
+fn json_list(row: &Row, index: usize) -> Result<Vec<String>> {
+    serde_json::from_str(text(row, index)?).map_err(|_| IndexerError::UnexpectedValue(index))
+}
+
+fn address(row: &Row, index: usize) -> Result<Pubkey> {
+    text(row, index)?.parse().map_err(|_| IndexerError::UnexpectedValue(index))
+}
+
+// The listing of an address as the program sees it at `now`, lapsed listings read as none
+fn effective_status(address: Pubkey, row: &Row, status: usize, expiry: usize, now: i64) -> Result<ListingStatus> {
+    let mut history = ReportHistory::new(address);
+    history.listing_status = parse_listing_status(text(row, status)?).ok_or(IndexerError::UnexpectedValue(status))?;
+    history.blacklist_expiry = int(row, expiry)?;
+    Ok(history.effective_status(now))
+}
+
+// Lookups over the tables kept by solcat-indexer, shaped like `solcat lookup --output json`
+pub struct Lookup<S> {
+    store: S,
+}
+
+impl<S: Store> Lookup<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    // The summary CheckAddress returns, zeroed for addresses nobody reported
+    pub fn risk_summary(&mut self, address: &Pubkey, now: i64) -> Result<Value> {
+        let mut summary = json!({
+            "address": address.to_string(),
+            "listing_status": listing_status_name(&ListingStatus::None),
+            "aggregate_score": 0,
+            "confidence": 0,
+            "total_reports": 0,
+            "distinct_reporters": 0,
+            "total_stake": 0,
+        });
+
+        let stats = self.store.query(
+            "SELECT aggregate_score, confidence, total_reports, distinct_reporters, total_stake, last_update, slot \
+             FROM address_stats WHERE address = $1",
+            &[(*address).into()],
+        )?;
+        if let Some(row) = stats.first() {
+            summary["aggregate_score"] = json!(int(row, 0)?);
+            summary["confidence"] = json!(int(row, 1)?);
+            summary["total_reports"] = json!(int(row, 2)?);
+            summary["distinct_reporters"] = json!(int(row, 3)?);
+            summary["total_stake"] = json!(int(row, 4)?);
+            summary["last_update"] = json!(int(row, 5)?);
+            summary["indexed_slot"] = json!(int(row, 6)?);
+        }
+
+        let histories = self.store.query(
+            "SELECT listing_status, blacklist_reason, blacklist_expiry FROM histories WHERE address = $1",
+            &[(*address).into()],
+        )?;
+        if let Some(row) = histories.first() {
+            let status = effective_status(*address, row, 0, 2, now)?;
+            summary["listing_status"] = json!(listing_status_name(&status));
+            summary["listing_reason"] = json!(text(row, 1)?);
+            summary["listing_expiry"] = json!(int(row, 2)?);
+        }
+        Ok(summary)
+    }
+
+    // Every report on an address with its assessment, the report history and the listing log
+    pub fn history(&mut self, address: &Pubkey, now: i64) -> Result<Value> {
+        let mut result = self.risk_summary(address, now)?;
+
+        let reports = self.store.query(
+            "SELECT account, reporter, risk_score, stake_amount, timestamp, description, vote_weight, last_update_time, \
+             base_score, risk_types, confidence_score, evidence_count, assessment_updated_at, transaction_volume, \
+             unique_interactions, age_of_account, suspicious_patterns, verified \
+             FROM reports WHERE reported_address = $1 ORDER BY timestamp, account",
+            &[(*address).into()],
+        )?;
+        result["reports"] = reports.iter().map(report_json).collect::<Result<_>>()?;
+
+        let history = self.store.query(
+            "SELECT r.timestamp, r.risk_score, r.reporter, r.description FROM history_reports r \
+             JOIN histories h ON h.account = r.history WHERE h.address = $1 ORDER BY r.position",
+            &[(*address).into()],
+        )?;
+        result["history"] = history
+            .iter()
+            .map(|row| {
+                Ok(json!({
+                    "timestamp": int(row, 0)?,
+                    "risk_score": int(row, 1)?,
+                    "reporter": text(row, 2)?,
+                    "description": text(row, 3)?,
+                }))
+            })
+            .collect::<Result<_>>()?;
+
+        let listings = self.store.query(
+            "SELECT l.action, l.status, l.authority, l.reason, l.timestamp, l.expiry FROM blacklist_log l \
+             JOIN histories h ON h.account = l.history WHERE h.address = $1 ORDER BY l.position",
+            &[(*address).into()],
+        )?;
+        result["listings"] = listings
+            .iter()
+            .map(|row| {
+                Ok(json!({
+                    "action": text(row, 0)?,
+                    "status": text(row, 1)?,
+                    "authority": text(row, 2)?,
+                    "reason": text(row, 3)?,
+                    "timestamp": int(row, 4)?,
+                    "expiry": int(row, 5)?,
+                }))
+            })
+            .collect::<Result<_>>()?;
+        Ok(result)
+    }
+
+    // Stats and recent reports and batches of a reporter, None if they never reported
+    pub fn reporter(&mut self, reporter: &Pubkey) -> Result<Option<Value>> {
+        let stats = self.store.query(
+            "SELECT total_reports, successful_reports, reputation_score, total_stake, delegated_stake, commission_bps, \
+             rewards_claimed, reward_points, last_report_time, cooldown_end_time, slot \
+             FROM reporter_stats WHERE account = $1",
+            &[pda::reporter_stats(reporter).into()],
+        )?;
+        let Some(row) = stats.first() else {
+            return Ok(None);
+        };
+        let mut result = json!({
+            "reporter": reporter.to_string(),
+            "total_reports": int(row, 0)?,
+            "successful_reports": int(row, 1)?,
+            "reputation_score": int(row, 2)?,
+            "total_stake": int(row, 3)?,
+            "delegated_stake": int(row, 4)?,
+            "commission_bps": int(row, 5)?,
+            "rewards_claimed": int(row, 6)?,
+            "reward_points": int(row, 7)?,
+            "last_report_time": int(row, 8)?,
+            "cooldown_end_time": int(row, 9)?,
+            "indexed_slot": int(row, 10)?,
+        });
+
+        let reports = self.store.query(
+            "SELECT account, reported_address, risk_score, timestamp, verified FROM reports \
+             WHERE reporter = $1 ORDER BY timestamp DESC, account LIMIT $2",
+            &[(*reporter).into(), SqlValue::from(MAX_TOP)],
+        )?;
+        result["reports"] = reports
+            .iter()
+            .map(|row| {
+                Ok(json!({
+                    "report": text(row, 0)?,
+                    "address": text(row, 1)?,
+                    "risk_score": int(row, 2)?,
+                    "timestamp": int(row, 3)?,
+                    "verified": int(row, 4)? != 0,
+                }))
+            })
+            .collect::<Result<_>>()?;
+
+        let batches = self.store.query(
+            "SELECT b.account, b.timestamp, b.verification_status, \
+             (SELECT COUNT(*) FROM batch_entries e WHERE e.batch = b.account) \
+             FROM batches b WHERE b.reporter = $1 ORDER BY b.timestamp DESC, b.account LIMIT $2",
+            &[(*reporter).into(), SqlValue::from(MAX_TOP)],
+        )?;
+        result["batches"] = batches
+            .iter()
+            .map(|row| {
+                Ok(json!({
+                    "batch": text(row, 0)?,
+                    "timestamp": int(row, 1)?,
+                    "verification_status": text(row, 2)?,
+                    "entries": int(row, 3)?,
+                }))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Some(result))
+    }
+
+    // Addresses by aggregate score, the most staked first among equal scores
+    pub fn top(&mut self, limit: u32, now: i64) -> Result<Value> {
+        let rows = self.store.query(
+            "SELECT s.address, s.aggregate_score, s.confidence, s.total_reports, s.distinct_reporters, s.total_stake, \
+             COALESCE(h.listing_status, $2), COALESCE(h.blacklist_expiry, 0) \
+             FROM address_stats s LEFT JOIN histories h ON h.address = s.address \
+             ORDER BY s.aggregate_score DESC, s.total_stake DESC, s.address LIMIT $1",
+            &[limit.min(MAX_TOP).into(), listing_status_name(&ListingStatus::None).into()],
+        )?;
+        rows.iter()
+            .map(|row| {
+                let status = effective_status(address(row, 0)?, row, 6, 7, now)?;
+                Ok(json!({
+                    "address": text(row, 0)?,
+                    "listing_status": listing_status_name(&status),
+                    "aggregate_score": int(row, 1)?,
+                    "confidence": int(row, 2)?,
+                    "total_reports": int(row, 3)?,
+                    "distinct_reporters": int(row, 4)?,
+                    "total_stake": int(row, 5)?,
+                }))
+            })
+            .collect()
+    }
+
+    // Listing changes in [since, now]: logged listings, amendments and lifts, and
+    // listings that lapsed at their expiry. Poll again with `since` set to `until`.
+    pub fn blacklist_since(&mut self, since: i64, now: i64) -> Result<Value> {
+        let mut changes: Vec<(i64, Value)> = self
+            .store
+            .query(
+                "SELECT h.address, l.action, l.status, l.reason, l.timestamp, l.expiry FROM blacklist_log l \
+                 JOIN histories h ON h.account = l.history \
+                 WHERE l.timestamp >= $1 AND l.timestamp <= $2 ORDER BY l.timestamp, h.address, l.position",
+                &[since.into(), now.into()],
+            )?
+            .iter()
+            .map(|row| {
+                let change = json!({
+                    "address": text(row, 0)?,
+                    "action": text(row, 1)?,
+                    "status": text(row, 2)?,
+                    "reason": text(row, 3)?,
+                    "timestamp": int(row, 4)?,
+                    "expiry": int(row, 5)?,
+                });
+                Ok((int(row, 4)?, change))
+            })
+            .collect::<Result<_>>()?;
+
+        let lapsed = self.store.query(
+            "SELECT address, blacklist_expiry FROM histories \
+             WHERE listing_status != $1 AND blacklist_expiry >= $2 AND blacklist_expiry <= $3",
+            &[listing_status_name(&ListingStatus::None).into(), since.into(), now.into()],
+        )?;
+        for row in &lapsed {
+            let change = json!({
+                "address": text(row, 0)?,
+                "action": "expire",
+                "status": listing_status_name(&ListingStatus::None),
+                "reason": "",
+                "timestamp": int(row, 1)?,
+                "expiry": int(row, 1)?,
+            });
+            changes.push((int(row, 1)?, change));
+        }
+        changes.sort_by_key(|(timestamp, _)| *timestamp);
+
+        Ok(json!({
+            "since": since,
+            "until": now,
+            "changes": changes.into_iter().map(|(_, change)| change).collect::<Vec<_>>(),
+        }))
+    }
+}
+
+// A report with the score the program's scoring gives its stored assessment and metrics
+fn report_json(row: &Row) -> Result<Value> {
+    let risk_types = json_list(row, 9)?;
+    let assessment = RiskAssessment {
+        base_score: int(row, 8)? as u8,
+        risk_types: risk_types
+            .iter()
+            .map(|name| parse_risk_type(name).ok_or(IndexerError::UnexpectedValue(9)))
+            .collect::<Result<_>>()?,
+        confidence_score: int(row, 10)? as u8,
+        evidence_count: int(row, 11)? as u32,
+        last_update: int(row, 12)?,
+    };
+    let metrics = RiskMetrics {
+        transaction_volume: int(row, 13)? as u64,
+        unique_interactions: int(row, 14)? as u32,
+        age_of_account: int(row, 15)?,
+        suspicious_patterns: json_list(row, 16)?,
+    };
+
+    Ok(json!({
+        "report": text(row, 0)?,
+        "reporter": text(row, 1)?,
+        "risk_score": int(row, 2)?,
+        "calculated_score": calculate_risk_score(&assessment, &metrics),
+        "stake_amount": int(row, 3)?,
+        "timestamp": int(row, 4)?,
+        "description": text(row, 5)?,
+        "vote_weight": int(row, 6)?,
+        "last_update_time": int(row, 7)?,
+        "risk_types": risk_types,
+        "confidence_score": assessment.confidence_score,
+        "evidence_count": assessment.evidence_count,
+        "suspicious_patterns": metrics.suspicious_patterns,
+        "verified": int(row, 17)? != 0,
+    }))
+}
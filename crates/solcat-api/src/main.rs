@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context};
+use clap::Parser;
+use tiny_http::{Header, Response, Server};
+
+use solcat_indexer::{connect, read_snapshot_dir, Indexer, Store};
+
+mod lookup;
+mod routes;
+
+use lookup::Lookup;
+
+#[derive(Parser)]
+#[command(name = "solcat-api", version, about = "Serve read-only JSON lookups over indexed SOLCAT data")]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, short = 'l', env = "SOLCAT_API_LISTEN", default_value = "127.0.0.1:8080")]
+    listen: String,
+    /// Database kept by solcat-indexer, a SQLite file or postgres:// URL
+    #[arg(long, short = 'd', env = "SOLCAT_DATABASE_URL", default_value = "solcat.db")]
+    database: String,
+    /// Serve a directory of account files instead, indexed in memory at start
+    #[arg(long, requires = "slot")]
+    snapshot: Option<PathBuf>,
+    /// Slot the snapshot accounts were dumped at
+    #[arg(long)]
+    slot: Option<u64>,
+}
+
+fn open_store(cli: &Cli) -> anyhow::Result<Box<dyn Store + Send>> {
+    match (&cli.snapshot, cli.slot) {
+        (Some(dir), Some(slot)) => {
+            let accounts = read_snapshot_dir(dir)?;
+            let mut indexer = Indexer::new(connect("sqlite::memory:")?)?;
+            let stats = indexer.index_snapshot(slot, &accounts, true)?;
+            eprintln!("indexed {}: {}", dir.display(), stats);
+            Ok(indexer.into_store())
+        }
+        _ => connect(&cli.database).with_context(|| format!("opening {}", cli.database)),
+    }
+}
+
+fn unix_timestamp() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+fn run(cli: Cli) -> anyhow::Result<()> {
+    let mut lookup = Lookup::new(open_store(&cli)?);
+    let server = Server::http(&cli.listen).map_err(|err| anyhow!("listening on {}: {}", cli.listen, err))?;
+    eprintln!("listening on http://{}", cli.listen);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    // Lookups are public and read-only, so the browser extension and app may call from any origin
+    let allow_origin = Header::from_bytes("Access-Control-Allow-Origin", "*").expect("valid header");
+
+    for request in server.incoming_requests() {
+        let (status, body) = routes::handle(&mut lookup, request.method().as_str(), request.url(), unix_timestamp());
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type.clone())
+            .with_header(allow_origin.clone());
+        if let Err(err) = request.respond(response) {
+            eprintln!("error: responding: {}", err);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use solcat_indexer::Store;
+
+use crate::lookup::Lookup;
+
+const DEFAULT_TOP: u32 = 20;
+
+// GET /v1/addresses/{address}            risk summary
+// GET /v1/addresses/{address}/history    reports, report history and listing log
+// GET /v1/reporters/{reporter}           reporter profile
+// GET /v1/top?limit=N                    riskiest addresses, at most 100
+// GET /v1/blacklist?since=UNIX_TIME      listing changes since a time, at or after 1970
+pub fn handle<S: Store>(lookup: &mut Lookup<S>, method: &str, url: &str, now: i64) -> (u16, Value) {
+    if method != "GET" {
+        return error(405, "only GET is supported");
+    }
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let result = match segments.as_slice() {
+        ["v1", "addresses", address] => match parse_address(address) {
+            Ok(address) => lookup.risk_summary(&address, now).map(Some),
+            Err(response) => return response,
+        },
+        ["v1", "addresses", address, "history"] => match parse_address(address) {
+            Ok(address) => lookup.history(&address, now).map(Some),
+            Err(response) => return response,
+        },
+        ["v1", "reporters", reporter] => match parse_address(reporter) {
+            Ok(reporter) => lookup.reporter(&reporter),
+            Err(response) => return response,
+        },
+        ["v1", "top"] => match query_param(query, "limit", DEFAULT_TOP) {
+            Ok(limit) => lookup.top(limit, now).map(Some),
+            Err(response) => return response,
+        },
+        ["v1", "blacklist"] => match query_param(query, "since", 0) {
+            Ok(since) if since >= 0 => lookup.blacklist_since(since, now).map(Some),
+            Ok(since) => return error(400, &format!("invalid since {:?}", since.to_string())),
+            Err(response) => return response,
+        },
+        _ => return error(404, "no such endpoint"),
+    };
+
+    match result {
+        Ok(Some(body)) => (200, body),
+        Ok(None) => error(404, "not indexed"),
+        Err(err) => error(500, &err.to_string()),
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, Value) {
+    (status, json!({ "error": message }))
+}
+
+fn parse_address(value: &str) -> Result<Pubkey, (u16, Value)> {
+    value.parse().map_err(|_| error(400, &format!("invalid address {:?}", value)))
+}
+
+fn query_param<T: std::str::FromStr>(query: &str, name: &str, default: T) -> Result<T, (u16, Value)> {
+    let pair = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| percent_decode(key).as_deref() == Some(name));
+    match pair {
+        Some((_, value)) => percent_decode(value)
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| error(400, &format!("invalid {} {:?}", name, value))),
+        None => Ok(default),
+    }
+}
+
+// Query strings are percent-encoded with `+` for a space, None for a malformed escape or UTF-8
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = value.bytes();
+    let mut decoded = Vec::with_capacity(value.len());
+    while let Some(byte) = bytes.next() {
+        decoded.push(match byte {
+            b'%' => {
+                let high = (bytes.next()? as char).to_digit(16)?;
+                let low = (bytes.next()? as char).to_digit(16)?;
+                (high * 16 + low) as u8
+            }
+            b'+' => b' ',
+            byte => byte,
+        });
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use solcat_client::solcat_program::calculate_risk_score;
+    use solcat_client::solcat_program::state::{
+        AddressReport, AddressStats, BlacklistAction, BlacklistRecord, ListingStatus, ReportHistory, RiskAssessment,
+        RiskMetrics, RiskType,
+    };
+    use solcat_client::AccountStubRpc;
+    use solcat_indexer::{connect, Indexer};
+
+    use super::*;
+
+    fn report(reported_address: Pubkey, risk_score: u8) -> AddressReport {
+        AddressReport {
+            reporter: Pubkey::new_unique(),
+            reported_address,
+            risk_score,
+            stake_amount: 1_000,
+            timestamp: 100,
+            description: "Wallet drainer".to_string(),
+            vote_weight: 100,
+            last_update_time: 100,
+            time_lock_end: 0,
+            risk_assessment: RiskAssessment {
+                base_score: 90,
+                risk_types: vec![RiskType::Phishing, RiskType::Malware],
+                confidence_score: 80,
+                evidence_count: 3,
+                last_update: 100,
+            },
+            risk_metrics: RiskMetrics {
+                transaction_volume: 250_000_000_000,
+                unique_interactions: 420,
+                age_of_account: 3_600,
+                suspicious_patterns: vec!["approval phishing".to_string(), "fresh funding".to_string()],
+            },
+            verified: true,
+        }
+    }
+
+    fn stats(address: Pubkey, score: u8, total_stake: u64) -> AddressStats {
+        AddressStats {
+            address,
+            total_reports: 1,
+            risk_scores: vec![score],
+            risk_score_times: vec![0],
+            total_stake,
+            last_update: 100,
+            last_report_time: 0,
+            weighted_risk_score: score as u128 * 100,
+            total_vote_weight: 100,
+            reporters: vec![Pubkey::new_unique()],
+            total_confidence: 80,
+        }
+    }
+
+    fn listed(address: Pubkey, timestamp: i64, expiry: i64) -> ReportHistory {
+        let mut history = ReportHistory::new(address);
+        history.listing_status = ListingStatus::Block;
+        history.blacklist_reason = "Drainer contract".to_string();
+        history.blacklist_timestamp = timestamp;
+        history.blacklist_expiry = expiry;
+        history.blacklist_log.push(BlacklistRecord {
+            action: BlacklistAction::Blacklist,
+            status: ListingStatus::Block,
+            authority: Pubkey::new_unique(),
+            reason: history.blacklist_reason.clone(),
+            timestamp,
+            expiry,
+        });
+        history
+    }
+
+    fn store(rpc: &AccountStubRpc) -> Box<dyn Store + Send> {
+        let mut indexer = Indexer::new(connect("sqlite::memory:").unwrap()).unwrap();
+        indexer.sync(rpc).unwrap();
+        indexer.into_store()
+    }
+
+    fn lookup(rpc: &AccountStubRpc) -> Lookup<Box<dyn Store + Send>> {
+        Lookup::new(store(rpc))
+    }
+
+    #[test]
+    fn serves_summaries_history_and_rankings_scored_like_the_program() {
+        let rpc = AccountStubRpc::new();
+        let (drainer, lapsed) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let drainer_report = report(drainer, 70);
+        rpc.set_program_account(Pubkey::new_unique(), &drainer_report);
+        rpc.set_program_account(Pubkey::new_unique(), &stats(drainer, 70, 5_000));
+        rpc.set_program_account(Pubkey::new_unique(), &stats(lapsed, 90, 1_000));
+        rpc.set_program_account(Pubkey::new_unique(), &listed(drainer, 200, 0));
+        rpc.set_program_account(Pubkey::new_unique(), &listed(lapsed, 150, 400));
+        let mut lookup = lookup(&rpc);
+
+        let (status, summary) = handle(&mut lookup, "GET", &format!("/v1/addresses/{}", drainer), 500);
+        assert_eq!(status, 200);
+        assert_eq!(summary["aggregate_score"], 70);
+        assert_eq!(summary["listing_status"], "block");
+
+        let (_, history) = handle(&mut lookup, "GET", &format!("/v1/addresses/{}/history", drainer), 500);
+        let expected = calculate_risk_score(&drainer_report.risk_assessment, &drainer_report.risk_metrics);
+        assert_eq!(history["reports"][0]["calculated_score"], expected);
+        assert_eq!(history["reports"][0]["risk_types"], json!(["phishing", "malware"]));
+        assert_eq!(history["listings"][0]["action"], "blacklist");
+
+        let (_, top) = handle(&mut lookup, "GET", "/v1/top?%6Cimit=%31", 500);
+        assert_eq!(top, json!([{
+            "address": lapsed.to_string(),
+            "listing_status": "none",
+            "aggregate_score": 90,
+            "confidence": 80,
+            "total_reports": 1,
+            "distinct_reporters": 1,
+            "total_stake": 1_000,
+        }]));
+
+        // The lapsed listing shows up as an expiry after the drainer was listed
+        let (_, delta) = handle(&mut lookup, "GET", "/v1/blacklist?since=180", 500);
+        let changes = delta["changes"].as_array().unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!((&changes[0]["address"], &changes[0]["action"]), (&json!(drainer.to_string()), &json!("blacklist")));
+        assert_eq!((&changes[1]["address"], &changes[1]["action"]), (&json!(lapsed.to_string()), &json!("expire")));
+        assert_eq!(delta["until"], 500);
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        let mut lookup = lookup(&AccountStubRpc::new());
+
+        assert_eq!(handle(&mut lookup, "GET", "/v1/addresses/not-a-key", 0).0, 400);
+        assert_eq!(handle(&mut lookup, "GET", "/v1/top?limit=many", 0).0, 400);
+        assert_eq!(handle(&mut lookup, "GET", "/v1/top?limit=%4", 0).0, 400);
+        assert_eq!(handle(&mut lookup, "GET", "/v1/blacklist?since=-1", 0).0, 400);
+        assert_eq!(handle(&mut lookup, "GET", "/v1/blacklist?since=%2D5", 0).0, 400);
+        assert_eq!(handle(&mut lookup, "GET", &format!("/v1/reporters/{}", Pubkey::new_unique()), 0).0, 404);
+        assert_eq!(handle(&mut lookup, "POST", "/v1/top", 0).0, 405);
+        assert_eq!(handle(&mut lookup, "GET", "/v2/top", 0).0, 404);
+
+        let (status, summary) = handle(&mut lookup, "GET", &format!("/v1/addresses/{}", Pubkey::new_unique()), 0);
+        assert_eq!((status, &summary["aggregate_score"]), (200, &json!(0)));
+    }
+
+    #[test]
+    fn fails_on_values_the_indexer_never_writes() {
+        let rpc = AccountStubRpc::new();
+        let address = Pubkey::new_unique();
+        rpc.set_program_account(Pubkey::new_unique(), &report(address, 70));
+        let mut store = store(&rpc);
+        store.execute("UPDATE reports SET risk_types = 'phishing'", &[]).unwrap();
+        let mut lookup = Lookup::new(store);
+
+        let (status, body) = handle(&mut lookup, "GET", &format!("/v1/addresses/{}/history", address), 0);
+        assert_eq!(status, 500);
+        assert_eq!(body["error"], "Result column 9 holds a value the index never writes");
+    }
+}
@@ -0,0 +1,628 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, ensure, Context as _, Result};
+use serde_json::{json, Map, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::signer::Signer;
+
+use solcat_client::names::{
+    emission_decay_name, listing_status_name, parse_emission_decay, parse_listing_status, parse_penalty_destination,
+    parse_risk_type, penalty_destination_name,
+};
+use solcat_client::solcat_program::instruction as args;
+use solcat_client::solcat_program::state::{
+    AddressRiskSummary, GlobalConfig, ListingStatus, ListingThreshold, ReportHistory, RiskMetrics, StakePool,
+    MAX_BATCH_SIZE,
+};
+use solcat_client::{events, instructions, pda, SolcatClient, SolcatRpc};
+use solcat_feeds::{misp_event, parse_feed, stix_bundle, FeedFormat, ImportOutcome, Importer, Progress, ThreatData};
+
+use crate::{BatchCommand, BlacklistCommand, Command, ConfigCommand, ConfigSetArgs, ExportFormat, ImportArgs, ReportArgs};
+
+pub struct Context<R = RpcClient> {
+    client: SolcatClient<R>,
+    keypair_path: Option<PathBuf>,
+    dry_run: bool,
+}
+
+impl Context {
+    pub fn new(url: &str, keypair_path: Option<PathBuf>, dry_run: bool) -> Self {
+        let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+        Self { client: SolcatClient::new(rpc), keypair_path, dry_run }
+    }
+}
+
+impl<R: SolcatRpc> Context<R> {
+    // The keypair is only read by commands that sign, defaulting to the Solana CLI's
+    fn signer(&self) -> Result<Keypair> {
+        let path = match &self.keypair_path {
+            Some(path) => path.clone(),
+            None => {
+                let home = std::env::var_os("HOME").ok_or_else(|| anyhow!("no --keypair given and HOME is not set"))?;
+                PathBuf::from(home).join(".config/solana/id.json")
+            }
+        };
+        read_keypair_file(&path).map_err(|err| anyhow!("failed to read keypair {}: {}", path.display(), err))
+    }
+
+    // Send the instructions, or simulate them with --dry-run, and add the outcome to `result`
+    fn submit(&self, payer: &Keypair, instructions: &[Instruction], extra_signers: &[&dyn Signer], result: Value) -> Result<Value> {
+        let mut signers: Vec<&dyn Signer> = vec![payer];
+        signers.extend_from_slice(extra_signers);
+
+        let mut result = match result {
+            Value::Object(fields) => fields,
+            _ => Map::new(),
+        };
+
+        if self.dry_run {
+            let simulation = self.client.simulate(instructions, &payer.pubkey(), &signers)?;
+            let emitted: Vec<String> = events::parse_logs(&simulation.logs).iter().map(|event| format!("{:?}", event)).collect();
+            result.insert("simulated".into(), json!(true));
+            result.insert("error".into(), json!(simulation.err));
+            result.insert("units_consumed".into(), json!(simulation.units_consumed));
+            result.insert("events".into(), json!(emitted));
+            result.insert("logs".into(), json!(simulation.logs));
+        } else {
+            let signature = self.client.send(instructions, &payer.pubkey(), &signers)?;
+            result.insert("signature".into(), json!(signature.to_string()));
+        }
+
+        Ok(Value::Object(result))
+    }
+}
+
+pub fn run<R: SolcatRpc>(context: &Context<R>, command: Command) -> Result<Value> {
+    match command {
+        Command::Report(report) => submit_report(context, report),
+        Command::UpdateReport { report, score, description } => {
+            ensure!(score <= 100, "risk score must be between 0 and 100");
+            let payer = context.signer()?;
+            let account = context.client.report(&report)?;
+            let instruction = instructions::update_report(&report, &account, score, description);
+            context.submit(&payer, &[instruction], &[], json!({ "report": report.to_string() }))
+        }
+        Command::Stake { amount, duration, position, token_account } => {
+            let payer = context.signer()?;
+            let config = context.client.config()?;
+            let instruction = instructions::stake_tokens(&config, &payer.pubkey(), &token_account, amount, duration, position);
+            let user_stake = pda::user_stake(&payer.pubkey(), position);
+            context.submit(&payer, &[instruction], &[], json!({ "user_stake": user_stake.to_string(), "amount": amount }))
+        }
+        Command::Unstake { amount, position, early } => {
+            let payer = context.signer()?;
+            let instruction = if early {
+                let config = context.client.config()?;
+                instructions::early_unstake(&config, &payer.pubkey(), position, amount)
+            } else {
+                instructions::unstake_tokens(&payer.pubkey(), position, amount)
+            };
+            let unbonding = pda::unbonding(&pda::user_stake(&payer.pubkey(), position));
+            context.submit(&payer, &[instruction], &[], json!({ "unbonding": unbonding.to_string(), "amount": amount }))
+        }
+        Command::Claim { token_account, position, reporter_rewards, delegated_to } => {
+            let payer = context.signer()?;
+            let config = context.client.config()?;
+            let (kind, instruction) = match (reporter_rewards, delegated_to) {
+                (true, _) => ("reporter", instructions::claim_reporter_rewards(&config, &payer.pubkey(), &token_account)),
+                (false, Some(reporter)) => (
+                    "delegation",
+                    instructions::claim_delegation_rewards(&config, &payer.pubkey(), &reporter, &token_account),
+                ),
+                (false, None) => ("staking", instructions::claim_rewards(&config, &payer.pubkey(), position, &token_account)),
+            };
+            context.submit(&payer, &[instruction], &[], json!({ "kind": kind }))
+        }
+        Command::Distribute => {
+            let payer = context.signer()?;
+            let config = context.client.config()?;
+            context.submit(&payer, &[instructions::distribute_rewards(&config)], &[], json!({}))
+        }
+        Command::Batch(BatchCommand::Submit { entries }) => submit_batch(context, &entries),
+        Command::Batch(BatchCommand::Verify { batch, reject }) => {
+            let payer = context.signer()?;
+            let account = context.client.batch_report(&batch)?;
+            let instruction = instructions::verify_batch_report(&payer.pubkey(), &batch, &account, !reject);
+            context.submit(&payer, &[instruction], &[], json!({ "batch_report": batch.to_string(), "verified": !reject }))
+        }
+        Command::Blacklist(BlacklistCommand::Add { address, reason, expiry, status }) => {
+            let status = parse_listing_status(&status).ok_or_else(|| anyhow!("unknown listing status {:?}", status))?;
+            ensure!(status != ListingStatus::None, "use `blacklist remove` to lift a listing");
+            let payer = context.signer()?;
+            let instruction = instructions::set_listing_status(&address, status, reason, expiry);
+            let result = json!({ "address": address.to_string(), "status": listing_status_name(&status) });
+            context.submit(&payer, &[instruction], &[], result)
+        }
+        Command::Blacklist(BlacklistCommand::Remove { address, rationale }) => {
+            let payer = context.signer()?;
+            let instruction = instructions::remove_from_blacklist(&address, rationale);
+            context.submit(&payer, &[instruction], &[], json!({ "address": address.to_string() }))
+        }
+        Command::Config(ConfigCommand::Show) => {
+            let config = context.client.config()?;
+            let stake_pool = context.client.stake_pool()?;
+            Ok(config_json(&config, &stake_pool))
+        }
+        Command::Config(ConfigCommand::Set(changes)) => {
+            let payer = context.signer()?;
+            let (instruction, result) = config_update(context, *changes)?;
+            context.submit(&payer, &[instruction], &[], result)
+        }
+        Command::Lookup { address } => lookup(context, &address),
+        Command::Import(import) => import_feed(context, import),
+        Command::Export { format, since } => {
+            let now = context.client.unix_timestamp()?;
+            let data = ThreatData::fetch(&context.client, since, now)?;
+            Ok(match format {
+                ExportFormat::Stix => stix_bundle(&data, now),
+                ExportFormat::Misp => misp_event(&data, now),
+            })
+        }
+    }
+}
+
+fn submit_report<R: SolcatRpc>(context: &Context<R>, report: ReportArgs) -> Result<Value> {
+    ensure!(report.score <= 100, "risk score must be between 0 and 100");
+    ensure!(report.confidence <= 100, "confidence must be between 0 and 100");
+    let risk_types = report
+        .risk_types
+        .iter()
+        .map(|name| parse_risk_type(name).ok_or_else(|| anyhow!("unknown risk type {:?}", name)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let payer = context.signer()?;
+    let report_account = Keypair::new();
+    let instruction = instructions::report_address(
+        &payer.pubkey(),
+        &report.address,
+        &report_account.pubkey(),
+        args::ReportAddress {
+            risk_score: report.score,
+            description: report.description,
+            risk_types,
+            confidence_score: report.confidence,
+            evidence_count: report.evidence_count,
+            risk_metrics: RiskMetrics {
+                transaction_volume: report.volume,
+                unique_interactions: report.interactions,
+                age_of_account: report.account_age,
+                suspicious_patterns: report.patterns,
+            },
+        },
+    );
+
+    let result = json!({ "report": report_account.pubkey().to_string(), "address": report.address.to_string() });
+    context.submit(&payer, &[instruction], &[&report_account], result)
+}
+
+fn submit_batch<R: SolcatRpc>(context: &Context<R>, entries: &[String]) -> Result<Value> {
+    ensure!(entries.len() <= MAX_BATCH_SIZE, "a batch holds at most {} addresses", MAX_BATCH_SIZE);
+
+    let mut addresses = Vec::with_capacity(entries.len());
+    let mut risk_scores = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let (address, score) = entry
+            .split_once(':')
+            .ok_or_else(|| anyhow!("batch entries are ADDRESS:SCORE, got {:?}", entry))?;
+        let score: u8 = score.parse().with_context(|| format!("invalid score in {:?}", entry))?;
+        ensure!(score <= 100, "risk score must be between 0 and 100, got {}", score);
+        addresses.push(address.parse::<Pubkey>().with_context(|| format!("invalid address in {:?}", entry))?);
+        risk_scores.push(score);
+    }
+
+    let payer = context.signer()?;
+    let batch = Keypair::new();
+    let instruction = instructions::submit_batch_report(&payer.pubkey(), &batch.pubkey(), addresses, risk_scores);
+    let result = json!({ "batch_report": batch.pubkey().to_string(), "entries": entries.len() });
+    context.submit(&payer, &[instruction], &[&batch], result)
+}
+
+fn import_feed<R: SolcatRpc>(context: &Context<R>, import: ImportArgs) -> Result<Value> {
+    let format = FeedFormat::from_path(&import.feed)?;
+    let file = File::open(&import.feed).with_context(|| format!("failed to open {}", import.feed.display()))?;
+    let feed = parse_feed(format, file)?;
+    let progress_path = import.progress.unwrap_or_else(|| {
+        let mut path = import.feed.clone().into_os_string();
+        path.push(".progress.json");
+        PathBuf::from(path)
+    });
+
+    let payer = context.signer()?;
+    let importer = Importer::new(&context.client, &payer)
+        .include_known(import.include_known)
+        .full_reports(import.full_reports);
+    if !import.full_reports {
+        eprintln!("batch reports only record addresses and scores, risk types and notes are not submitted (see --full-reports)");
+    }
+
+    let rejected: Vec<Value> = feed
+        .rejected
+        .iter()
+        .map(|rejected| json!({ "record": rejected.record, "reason": rejected.reason }))
+        .collect();
+    let mut result = Map::new();
+    result.insert("entries".into(), json!(feed.entries.len()));
+    result.insert("duplicates".into(), json!(feed.duplicates));
+    result.insert("rejected".into(), json!(rejected));
+    result.insert("progress_file".into(), json!(progress_path.display().to_string()));
+
+    // A dry run only reports what would be submitted
+    if context.dry_run {
+        let progress = Progress::load(&progress_path)?.unwrap_or_default();
+        let (pending, skipped_known, skipped_submitted) = importer.pending(&feed.entries, &progress)?;
+        result.insert("skipped_known".into(), json!(skipped_known));
+        result.insert("skipped_submitted".into(), json!(skipped_submitted));
+        result.insert("pending".into(), json!(pending.len()));
+        result.insert("batches".into(), json!(pending.chunks(importer.submission_size()).count()));
+        return Ok(Value::Object(result));
+    }
+
+    let mut total = importer.run(&feed.entries, &progress_path)?;
+    while let (true, Some(resume_at)) = (import.wait, total.resume_at) {
+        let wait = resume_at.saturating_sub(context.client.unix_timestamp()?).max(1);
+        eprintln!("waiting {}s for the reporter's limits, {} entries remaining", wait, total.remaining);
+        thread::sleep(Duration::from_secs(wait as u64));
+
+        let next = importer.run(&feed.entries, &progress_path)?;
+        total = ImportOutcome {
+            submitted_batches: total.submitted_batches + next.submitted_batches,
+            submitted_entries: total.submitted_entries + next.submitted_entries,
+            ..next
+        };
+    }
+
+    result.insert("submitted_batches".into(), json!(total.submitted_batches));
+    result.insert("submitted_entries".into(), json!(total.submitted_entries));
+    result.insert("skipped_known".into(), json!(total.skipped_known));
+    result.insert("skipped_submitted".into(), json!(total.skipped_submitted));
+    result.insert("remaining".into(), json!(total.remaining));
+    result.insert("resume_at".into(), json!(total.resume_at));
+    Ok(Value::Object(result))
+}
+
+fn lookup<R: SolcatRpc>(context: &Context<R>, address: &Pubkey) -> Result<Value> {
+    let now = context.client.unix_timestamp()?;
+    let summary = context.client.risk_summary(address, now)?;
+    let stats = context.client.address_stats(address)?;
+    let history = context.client.report_history(address)?;
+
+    let mut result = summary_json(&summary);
+    if let Value::Object(fields) = &mut result {
+        if let Some(stats) = stats {
+            fields.insert("risk_scores".into(), json!(stats.risk_scores));
+            fields.insert("last_update".into(), json!(stats.last_update));
+        }
+        if let Some(history) = history {
+            fields.insert("listing_reason".into(), json!(history.blacklist_reason));
+            fields.insert("listing_expiry".into(), json!(history.blacklist_expiry));
+            fields.insert("reports".into(), history_reports_json(&history));
+        }
+    }
+    Ok(result)
+}
+
+// The update_config instruction carrying the current config with the changes applied
+fn config_update<R: SolcatRpc>(context: &Context<R>, changes: ConfigSetArgs) -> Result<(Instruction, Value)> {
+    let mut config = context.client.config()?;
+    apply_config_changes(&mut config, changes)?;
+    let stake_pool = context.client.stake_pool()?;
+    let result = config_json(&config, &stake_pool);
+    Ok((instructions::update_config(config), result))
+}
+
+fn apply_config_changes(config: &mut GlobalConfig, changes: ConfigSetArgs) -> Result<()> {
+    if let Some(value) = changes.min_stake_amount {
+        config.min_stake_amount = value;
+    }
+    if let Some(value) = changes.staking_enabled {
+        config.staking_enabled = value;
+    }
+    if let Some(value) = changes.min_lock_duration {
+        config.min_lock_duration = value;
+    }
+    if let Some(value) = changes.unbonding_period {
+        config.unbonding_period = value;
+    }
+    if let Some(value) = changes.early_unstake_penalty_bps {
+        config.early_unstake_penalty_bps = value;
+    }
+    if let Some(name) = changes.early_unstake_penalty_destination {
+        config.early_unstake_penalty_destination =
+            parse_penalty_destination(&name).ok_or_else(|| anyhow!("unknown penalty destination {:?}", name))?;
+    }
+    if let Some(value) = changes.token_mint {
+        config.token_mint = value;
+    }
+    if let Some(value) = changes.treasury {
+        config.treasury = value;
+    }
+    if let Some(value) = changes.stake_vault {
+        config.stake_vault = value;
+    }
+    if let Some(value) = changes.total_supply {
+        config.total_supply = value;
+    }
+    if let Some(value) = changes.circulating_supply {
+        config.circulating_supply = value;
+    }
+
+    let thresholds = &mut config.listing_thresholds;
+    for (rule, threshold) in [
+        (changes.watch_threshold, &mut thresholds.watch),
+        (changes.warn_threshold, &mut thresholds.warn),
+        (changes.block_threshold, &mut thresholds.block),
+    ] {
+        if let Some(rule) = rule {
+            *threshold = parse_threshold(&rule)?;
+        }
+    }
+
+    let schedule = &mut config.emission_schedule;
+    if let Some(value) = changes.emission_start_time {
+        schedule.start_time = value;
+    }
+    if let Some(value) = changes.emission_end_time {
+        schedule.end_time = value;
+    }
+    if let Some(value) = changes.emission_epoch_duration {
+        schedule.epoch_duration = value;
+    }
+    if let Some(value) = changes.emission_initial_epoch {
+        schedule.initial_epoch_emission = value;
+    }
+    if let Some(name) = changes.emission_decay {
+        schedule.decay = parse_emission_decay(&name).ok_or_else(|| anyhow!("unknown emission decay {:?}", name))?;
+    }
+    if let Some(value) = changes.emission_decay_param {
+        schedule.decay_param = value;
+    }
+    Ok(())
+}
+
+// A tier rule is SCORE:REPORTERS:STAKE, `none` disables the tier
+fn parse_threshold(rule: &str) -> Result<Option<ListingThreshold>> {
+    if rule.trim().eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let parts: Vec<&str> = rule.split(':').collect();
+    let [score, reporters, stake] = parts[..] else {
+        return Err(anyhow!("listing thresholds are SCORE:REPORTERS:STAKE or none, got {:?}", rule));
+    };
+    let threshold = ListingThreshold {
+        min_aggregate_score: score.parse().with_context(|| format!("invalid score in {:?}", rule))?,
+        min_distinct_reporters: reporters.parse().with_context(|| format!("invalid reporter count in {:?}", rule))?,
+        min_total_stake: stake.parse().with_context(|| format!("invalid stake in {:?}", rule))?,
+    };
+    ensure!(threshold.min_aggregate_score <= 100, "risk score must be between 0 and 100, got {}", threshold.min_aggregate_score);
+    Ok(Some(threshold))
+}
+
+fn summary_json(summary: &AddressRiskSummary) -> Value {
+    json!({
+        "address": summary.address.to_string(),
+        "listing_status": listing_status_name(&summary.listing_status),
+        "aggregate_score": summary.aggregate_score,
+        "confidence": summary.confidence,
+        "total_reports": summary.total_reports,
+        "distinct_reporters": summary.distinct_reporters,
+        "total_stake": summary.total_stake,
+    })
+}
+
+fn history_reports_json(history: &ReportHistory) -> Value {
+    history
+        .reports
+        .iter()
+        .map(|report| {
+            json!({
+                "timestamp": report.timestamp,
+                "risk_score": report.risk_score,
+                "reporter": report.reporter.to_string(),
+                "description": report.description,
+            })
+        })
+        .collect()
+}
+
+fn config_json(config: &GlobalConfig, stake_pool: &StakePool) -> Value {
+    let threshold = |threshold: &Option<ListingThreshold>| match threshold {
+        Some(t) => json!(format!(
+            "score >= {}, reporters >= {}, stake >= {}",
+            t.min_aggregate_score, t.min_distinct_reporters, t.min_total_stake
+        )),
+        None => Value::Null,
+    };
+
+    json!({
+        "token_mint": config.token_mint.to_string(),
+        "treasury": config.treasury.to_string(),
+        "stake_vault": config.stake_vault.to_string(),
+        "staking_enabled": config.staking_enabled,
+        "min_stake_amount": config.min_stake_amount,
+        "min_lock_duration": config.min_lock_duration,
+        "unbonding_period": config.unbonding_period,
+        "early_unstake_penalty_bps": config.early_unstake_penalty_bps,
+        "early_unstake_penalty_destination": penalty_destination_name(&config.early_unstake_penalty_destination),
+        "watch_threshold": threshold(&config.listing_thresholds.watch),
+        "warn_threshold": threshold(&config.listing_thresholds.warn),
+        "block_threshold": threshold(&config.listing_thresholds.block),
+        "total_supply": config.total_supply,
+        "circulating_supply": config.circulating_supply,
+        "emission_start_time": config.emission_schedule.start_time,
+        "emission_end_time": config.emission_schedule.end_time,
+        "emission_epoch_duration": config.emission_schedule.epoch_duration,
+        "emission_initial_epoch": config.emission_schedule.initial_epoch_emission,
+        "emission_decay": emission_decay_name(&config.emission_schedule.decay),
+        "emission_decay_param": config.emission_schedule.decay_param,
+        "stake_pool_epoch": stake_pool.epoch,
+        "total_staked": stake_pool.total_staked,
+        "total_distributed": stake_pool.total_distributed,
+        "total_paid": stake_pool.total_paid,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use solana_sdk::signature::write_keypair_file;
+    use solcat_client::solcat_program::state::{EmissionDecay, EmissionSchedule, ListingThresholds, PenaltyDestination};
+    use solcat_client::{AccountStubRpc, Simulation};
+
+    use super::*;
+    use crate::Cli;
+
+    fn config() -> GlobalConfig {
+        GlobalConfig {
+            min_stake_amount: 1_000_000,
+            emission_schedule: EmissionSchedule::default(),
+            token_mint: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            total_supply: 0,
+            circulating_supply: 0,
+            staking_enabled: false,
+            min_lock_duration: 0,
+            listing_thresholds: ListingThresholds {
+                watch: Some(ListingThreshold { min_aggregate_score: 30, min_distinct_reporters: 1, min_total_stake: 0 }),
+                ..ListingThresholds::default()
+            },
+            stake_vault: Pubkey::new_unique(),
+            early_unstake_penalty_bps: 0,
+            early_unstake_penalty_destination: PenaltyDestination::Treasury,
+            unbonding_period: 0,
+        }
+    }
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(["solcat"].iter().chain(args)).unwrap()
+    }
+
+    fn config_changes(cli: Cli) -> ConfigSetArgs {
+        match cli.command {
+            Command::Config(ConfigCommand::Set(changes)) => *changes,
+            _ => panic!("not a config set command"),
+        }
+    }
+
+    // A context over an account stub that holds the config and stake pool
+    fn stub_context(cli: &Cli, config: &GlobalConfig) -> Context<AccountStubRpc> {
+        let rpc = AccountStubRpc::new();
+        rpc.set_program_account(pda::config(), config);
+        rpc.set_program_account(pda::stake_pool(), &StakePool {
+            total_staked: 0,
+            total_weight: 0,
+            reward_per_token: 0,
+            last_update_time: 0,
+            emission_schedule: config.emission_schedule.clone(),
+            epoch: 0,
+            epoch_emitted: 0,
+            total_distributed: 0,
+            total_paid: 0,
+            emission_cap: 0,
+        });
+
+        let keypair_path = std::env::temp_dir().join(format!("solcat-cli-{}.json", Pubkey::new_unique()));
+        write_keypair_file(&Keypair::new(), &keypair_path).unwrap();
+        Context { client: SolcatClient::new(rpc), keypair_path: Some(keypair_path), dry_run: cli.dry_run }
+    }
+
+    #[test]
+    fn config_set_changes_only_the_given_fields() {
+        let mint = Pubkey::new_unique();
+        let cli = parse(&[
+            "config", "set",
+            "--token-mint", &mint.to_string(),
+            "--total-supply", "1000000",
+            "--circulating-supply", "250000",
+            "--early-unstake-penalty-destination", "stakers",
+            "--watch-threshold", "none",
+            "--block-threshold", "80:3:5000",
+            "--emission-start-time", "100",
+            "--emission-end-time", "900",
+            "--emission-epoch-duration", "60",
+            "--emission-initial-epoch", "1000",
+            "--emission-decay", "halving",
+            "--emission-decay-param", "4",
+        ]);
+        let mut config = config();
+        let treasury = config.treasury;
+        apply_config_changes(&mut config, config_changes(cli)).unwrap();
+
+        assert_eq!(config.token_mint, mint);
+        assert_eq!((config.total_supply, config.circulating_supply), (1_000_000, 250_000));
+        assert_eq!(config.early_unstake_penalty_destination, PenaltyDestination::Stakers);
+        assert!(config.listing_thresholds.watch.is_none() && config.listing_thresholds.warn.is_none());
+        let block = config.listing_thresholds.block.unwrap();
+        assert_eq!((block.min_aggregate_score, block.min_distinct_reporters, block.min_total_stake), (80, 3, 5_000));
+        assert_eq!(
+            config.emission_schedule,
+            EmissionSchedule {
+                start_time: 100,
+                end_time: 900,
+                epoch_duration: 60,
+                initial_epoch_emission: 1_000,
+                decay: EmissionDecay::Halving,
+                decay_param: 4,
+            }
+        );
+        assert_eq!((config.treasury, config.min_stake_amount), (treasury, 1_000_000));
+    }
+
+    #[test]
+    fn config_set_rejects_malformed_values() {
+        for args in [
+            ["--block-threshold", "80:3"],
+            ["--block-threshold", "101:3:0"],
+            ["--early-unstake-penalty-destination", "burn"],
+            ["--emission-decay", "exponential"],
+        ] {
+            let cli = parse(&["config", "set", args[0], args[1]]);
+            assert!(apply_config_changes(&mut config(), config_changes(cli)).is_err(), "{:?} was accepted", args);
+        }
+    }
+
+    #[test]
+    fn config_set_builds_the_changed_config() {
+        let cli = parse(&["config", "set", "--unbonding-period", "3600"]);
+        let config = config();
+        let context = stub_context(&cli, &config);
+
+        let (instruction, result) = config_update(&context, config_changes(cli)).unwrap();
+        assert_eq!(result["unbonding_period"], json!(3600));
+        let mut expected = config.clone();
+        expected.unbonding_period = 3_600;
+        assert_eq!(instruction, instructions::update_config(expected));
+    }
+
+    #[test]
+    fn dry_run_simulates_without_sending() {
+        let entry = format!("{}:70", Pubkey::new_unique());
+        let cli = parse(&["batch", "submit", &entry]);
+        let context = stub_context(&cli, &config());
+        let result = run(&context, cli.command).unwrap();
+        assert!(result["signature"].is_string());
+        assert_eq!(context.client.rpc().transactions().len(), 1);
+
+        let cli = parse(&["--dry-run", "batch", "submit", &entry]);
+        let context = stub_context(&cli, &config());
+        context.client.rpc().set_simulation(Simulation {
+            err: None,
+            logs: vec!["Program log: Batch report submitted".into()],
+            units_consumed: Some(5_000),
+        });
+
+        let result = run(&context, cli.command).unwrap();
+        assert_eq!(result["simulated"], json!(true));
+        assert_eq!(result["units_consumed"], json!(5_000));
+        assert_eq!(result["logs"], json!(["Program log: Batch report submitted"]));
+        assert!(result.get("signature").is_none());
+        assert!(context.client.rpc().transactions().is_empty());
+    }
+}
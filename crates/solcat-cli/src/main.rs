@@ -0,0 +1,271 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use solana_sdk::pubkey::Pubkey;
+
+mod commands;
+mod output;
+
+use output::OutputFormat;
+
+#[derive(Parser)]
+#[command(name = "solcat", version, about = "Operate and report to the SOLCAT program")]
+struct Cli {
+    /// RPC endpoint of the cluster
+    #[arg(long, short = 'u', global = true, env = "SOLCAT_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Keypair that signs and pays, admin commands need the program keypair
+    #[arg(long, short = 'k', global = true, env = "SOLCAT_KEYPAIR")]
+    keypair: Option<PathBuf>,
+    /// How results are printed
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+    /// Simulate transactions and print their logs and events instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Report an address with a full risk assessment
+    Report(ReportArgs),
+    /// Change the score and description of one of your reports
+    UpdateReport {
+        report: Pubkey,
+        #[arg(long)]
+        score: u8,
+        #[arg(long)]
+        description: String,
+    },
+    /// Stake tokens in a stake position
+    Stake {
+        amount: u64,
+        /// Lock duration in seconds
+        #[arg(long)]
+        duration: i64,
+        #[arg(long, default_value_t = 0)]
+        position: u32,
+        #[arg(long)]
+        token_account: Pubkey,
+    },
+    /// Start unbonding tokens from a stake position
+    Unstake {
+        amount: u64,
+        #[arg(long, default_value_t = 0)]
+        position: u32,
+        /// Leave before the lock ends, paying the early unstake penalty
+        #[arg(long)]
+        early: bool,
+    },
+    /// Claim staking, reporter or delegation rewards
+    Claim {
+        #[arg(long)]
+        token_account: Pubkey,
+        #[arg(long, default_value_t = 0, conflicts_with_all = ["reporter_rewards", "delegated_to"])]
+        position: u32,
+        /// Claim rewards earned as a reporter
+        #[arg(long)]
+        reporter_rewards: bool,
+        /// Claim rewards of a delegation to this reporter
+        #[arg(long, conflicts_with = "reporter_rewards")]
+        delegated_to: Option<Pubkey>,
+    },
+    /// Distribute accrued staking rewards (admin)
+    Distribute,
+    /// Submit or verify batch reports
+    #[command(subcommand)]
+    Batch(BatchCommand),
+    /// List addresses or lift their listing
+    #[command(subcommand)]
+    Blacklist(BlacklistCommand),
+    /// Show or change the global config
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Show the risk summary, reports and listing of an address
+    Lookup { address: Pubkey },
+    /// Import a CSV or JSON threat feed as batch reports
+    Import(ImportArgs),
+    /// Export reports and listings as a STIX 2.1 bundle or MISP event, always as JSON
+    Export {
+        #[arg(value_enum)]
+        format: ExportFormat,
+        /// Only export what changed at or after this unix time
+        #[arg(long, default_value_t = 0)]
+        since: i64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Stix,
+    Misp,
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// Feed file with address, risk_type, score and notes per entry
+    pub feed: PathBuf,
+    /// Progress file, an existing one is resumed [default: <FEED>.progress.json]
+    #[arg(long)]
+    pub progress: Option<PathBuf>,
+    /// Also import addresses that already have address stats
+    #[arg(long)]
+    pub include_known: bool,
+    /// Wait out cooldowns and report windows instead of stopping at them
+    #[arg(long)]
+    pub wait: bool,
+    /// Submit each entry as a full report with its risk type and notes instead of
+    /// batch reports, which only record addresses and scores
+    #[arg(long)]
+    pub full_reports: bool,
+}
+
+#[derive(Args)]
+pub struct ReportArgs {
+    pub address: Pubkey,
+    #[arg(long)]
+    pub score: u8,
+    #[arg(long)]
+    pub description: String,
+    /// Risk type, repeat for several (scam, phishing, malware, ransomware, money_laundering, ...)
+    #[arg(long = "risk-type", required = true)]
+    pub risk_types: Vec<String>,
+    #[arg(long, default_value_t = 50)]
+    pub confidence: u8,
+    #[arg(long, default_value_t = 0)]
+    pub evidence_count: u32,
+    /// Transaction volume of the address in lamports
+    #[arg(long, default_value_t = 0)]
+    pub volume: u64,
+    #[arg(long, default_value_t = 0)]
+    pub interactions: u32,
+    /// Age of the address in seconds
+    #[arg(long, default_value_t = i64::MAX)]
+    pub account_age: i64,
+    /// Suspicious pattern observed, repeat for several
+    #[arg(long = "pattern")]
+    pub patterns: Vec<String>,
+}
+
+#[derive(Subcommand)]
+pub enum BatchCommand {
+    /// Submit up to MAX_BATCH_SIZE scores as ADDRESS:SCORE pairs
+    Submit {
+        #[arg(required = true)]
+        entries: Vec<String>,
+    },
+    /// Verify or reject a pending batch report
+    Verify {
+        batch: Pubkey,
+        #[arg(long)]
+        reject: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BlacklistCommand {
+    /// List an address (admin)
+    Add {
+        address: Pubkey,
+        #[arg(long)]
+        reason: String,
+        /// Unix time the listing lapses at, 0 for permanent
+        #[arg(long, default_value_t = 0)]
+        expiry: i64,
+        /// Listing tier: watch, warn, block or sanctioned
+        #[arg(long, default_value = "block")]
+        status: String,
+    },
+    /// Lift the listing of an address (admin)
+    Remove {
+        address: Pubkey,
+        #[arg(long)]
+        rationale: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Show the global config and stake pool
+    Show,
+    /// Change global config fields, the rest are kept (admin)
+    Set(Box<ConfigSetArgs>),
+}
+
+#[derive(Args)]
+pub struct ConfigSetArgs {
+    #[arg(long)]
+    pub min_stake_amount: Option<u64>,
+    #[arg(long)]
+    pub staking_enabled: Option<bool>,
+    #[arg(long)]
+    pub min_lock_duration: Option<i64>,
+    #[arg(long)]
+    pub unbonding_period: Option<i64>,
+    #[arg(long)]
+    pub early_unstake_penalty_bps: Option<u16>,
+    /// Where early unstake penalties go: treasury or stakers
+    #[arg(long)]
+    pub early_unstake_penalty_destination: Option<String>,
+    #[arg(long)]
+    pub token_mint: Option<Pubkey>,
+    #[arg(long)]
+    pub treasury: Option<Pubkey>,
+    #[arg(long)]
+    pub stake_vault: Option<Pubkey>,
+    #[arg(long)]
+    pub total_supply: Option<u64>,
+    #[arg(long)]
+    pub circulating_supply: Option<u64>,
+    /// Watch tier rule as SCORE:REPORTERS:STAKE, or none to disable the tier
+    #[arg(long)]
+    pub watch_threshold: Option<String>,
+    /// Warn tier rule as SCORE:REPORTERS:STAKE, or none to disable the tier
+    #[arg(long)]
+    pub warn_threshold: Option<String>,
+    /// Block tier rule as SCORE:REPORTERS:STAKE, or none to disable the tier
+    #[arg(long)]
+    pub block_threshold: Option<String>,
+    /// Unix time emission starts at
+    #[arg(long)]
+    pub emission_start_time: Option<i64>,
+    /// Unix time emission ends at
+    #[arg(long)]
+    pub emission_end_time: Option<i64>,
+    /// Emission epoch length in seconds
+    #[arg(long)]
+    pub emission_epoch_duration: Option<i64>,
+    /// Budget of the first emission epoch
+    #[arg(long)]
+    pub emission_initial_epoch: Option<u64>,
+    /// How the epoch budget decays: constant, halving or linear
+    #[arg(long)]
+    pub emission_decay: Option<String>,
+    /// Epochs between halvings, or the amount a linear decay removes each epoch
+    #[arg(long)]
+    pub emission_decay_param: Option<u64>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let context = commands::Context::new(&cli.url, cli.keypair, cli.dry_run);
+    // Exports are documents for other tools, not results to tabulate
+    let format = match cli.command {
+        Command::Export { .. } => OutputFormat::Json,
+        _ => cli.output,
+    };
+
+    match commands::run(&context, cli.command) {
+        Ok(value) => {
+            println!("{}", output::render(format, &value));
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
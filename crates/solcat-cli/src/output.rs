@@ -0,0 +1,94 @@
+use clap::ValueEnum;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+pub fn render(format: OutputFormat, value: &Value) -> String {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(value).unwrap_or_default(),
+        OutputFormat::Table => render_table(value),
+    }
+}
+
+// Objects print as field/value rows, arrays of objects as one row per element
+fn render_table(value: &Value) -> String {
+    match value {
+        Value::Object(fields) => table(
+            &["FIELD", "VALUE"],
+            fields.iter().map(|(key, value)| vec![key.clone(), cell(value)]).collect(),
+        ),
+        Value::Array(items) => {
+            let Some(Value::Object(first)) = items.first() else {
+                return items.iter().map(cell).collect::<Vec<_>>().join("\n");
+            };
+            let headers: Vec<String> = first.keys().map(|key| key.to_uppercase()).collect();
+            let rows = items
+                .iter()
+                .map(|item| first.keys().map(|key| item.get(key).map(cell).unwrap_or_default()).collect())
+                .collect();
+            table(&headers, rows)
+        }
+        other => cell(other),
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(|item| !item.is_object()) => {
+            items.iter().map(cell).collect::<Vec<_>>().join(", ")
+        }
+        other => other.to_string(),
+    }
+}
+
+fn table<H: AsRef<str>>(headers: &[H], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.as_ref().len()).collect();
+    for row in &rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+
+    let line = |values: Vec<&str>| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{:<width$}", value, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![line(headers.iter().map(|h| h.as_ref()).collect())];
+    lines.extend(rows.iter().map(|row| line(row.iter().map(String::as_str).collect())));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn renders_objects_and_arrays_as_tables() {
+        let object = json!({ "address": "abc", "score": 70, "reporters": ["x", "y"] });
+        assert_eq!(
+            render(OutputFormat::Table, &object),
+            "FIELD      VALUE\naddress    abc\nscore      70\nreporters  x, y"
+        );
+
+        let rows = json!([{ "address": "abc", "score": 70 }, { "address": "defgh", "score": null }]);
+        assert_eq!(
+            render(OutputFormat::Table, &rows),
+            "ADDRESS  SCORE\nabc      70\ndefgh    -"
+        );
+    }
+}
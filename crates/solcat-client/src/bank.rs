@@ -0,0 +1,184 @@
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use anchor_lang::AccountSerialize;
+use solana_program_test::tokio::runtime::{Builder, Runtime};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::clock::Clock;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+
+use crate::error::{ClientError, Result};
+use crate::rpc::{Simulation, SolcatRpc, TransactionLogs};
+
+// A bank for tests that runs the program in-process on solana-program-test, so sent
+// transactions execute and change accounts as on chain. BanksClient has no account
+// scan, get_program_accounts covers the accounts set or touched through this bank.
+pub struct BankRpc {
+    runtime: Runtime,
+    state: Mutex<BankState>,
+}
+
+struct BankState {
+    context: ProgramTestContext,
+    known: BTreeSet<Pubkey>,
+    // Logs of successful transactions with the accounts they mentioned
+    transaction_logs: Vec<(Vec<Pubkey>, TransactionLogs)>,
+}
+
+// Anchor's entry borrows the accounts for as long as the instruction runs
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    solcat_program::entry(program_id, accounts, data)
+}
+
+fn bank_error(err: BanksClientError) -> ClientError {
+    ClientError::Rpc(err.to_string())
+}
+
+impl BankRpc {
+    pub fn new() -> Self {
+        let runtime = Builder::new_current_thread().enable_all().build().expect("test runtime starts");
+        let mut program_test = ProgramTest::new("solcat_program", solcat_program::ID, processor!(process_instruction));
+        program_test.prefer_bpf(false);
+        let context = runtime.block_on(program_test.start_with_context());
+        Self {
+            runtime,
+            state: Mutex::new(BankState { context, known: BTreeSet::new(), transaction_logs: Vec::new() }),
+        }
+    }
+
+    // The bank's funded fee payer
+    pub fn payer(&self) -> Keypair {
+        self.state.lock().unwrap().context.payer.insecure_clone()
+    }
+
+    pub fn set_account(&self, address: Pubkey, account: Account) {
+        let mut state = self.state.lock().unwrap();
+        state.context.set_account(&address, &AccountSharedData::from(account));
+        state.known.insert(address);
+    }
+
+    // Stores a program account the way Anchor writes it, discriminator first
+    pub fn set_program_account<T: AccountSerialize>(&self, address: Pubkey, value: &T) {
+        self.set_program_account_with_space(address, value, 0);
+    }
+
+    // Like set_program_account, padded to the `space` the program allocates,
+    // which init_if_needed accounts are checked against
+    pub fn set_program_account_with_space<T: AccountSerialize>(&self, address: Pubkey, value: &T, space: usize) {
+        let mut data = Vec::new();
+        value.try_serialize(&mut data).expect("account serializes");
+        data.resize(data.len().max(space), 0);
+        let lamports = self.rent_exempt_minimum(data.len());
+        self.set_account(address, Account { lamports, data, owner: solcat_program::ID, executable: false, rent_epoch: 0 });
+    }
+
+    // Funds a system account, such as a signer paying for its own reports
+    pub fn airdrop(&self, address: &Pubkey, lamports: u64) {
+        self.set_account(*address, Account::new(lamports, 0, &system_program::ID));
+    }
+
+    // Moves the clock the program reads to the given time
+    pub fn set_clock(&self, unix_timestamp: i64) {
+        let mut state = self.state.lock().unwrap();
+        let mut clock: Clock = self.runtime.block_on(state.context.banks_client.get_sysvar()).expect("clock sysvar");
+        clock.unix_timestamp = unix_timestamp;
+        state.context.set_sysvar(&clock);
+    }
+
+    fn rent_exempt_minimum(&self, data_len: usize) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let rent = self.runtime.block_on(state.context.banks_client.get_rent()).expect("rent sysvar");
+        rent.minimum_balance(data_len)
+    }
+}
+
+impl Default for BankRpc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SolcatRpc for BankRpc {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        let mut state = self.state.lock().unwrap();
+        self.runtime.block_on(state.context.banks_client.get_account(*address)).map_err(bank_error)
+    }
+
+    fn get_program_accounts(&self, program_id: &Pubkey) -> Result<Vec<(Pubkey, Account)>> {
+        let known: Vec<Pubkey> = self.state.lock().unwrap().known.iter().copied().collect();
+        let mut accounts = Vec::new();
+        for address in known {
+            if let Some(account) = self.get_account(&address)?.filter(|account| account.owner == *program_id) {
+                accounts.push((address, account));
+            }
+        }
+        Ok(accounts)
+    }
+
+    fn get_slot(&self) -> Result<u64> {
+        let mut state = self.state.lock().unwrap();
+        self.runtime.block_on(state.context.banks_client.get_root_slot()).map_err(bank_error)
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash> {
+        let mut state = self.state.lock().unwrap();
+        self.runtime.block_on(state.context.banks_client.get_latest_blockhash()).map_err(bank_error)
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        transaction.verify().map_err(|err| ClientError::Rpc(err.to_string()))?;
+
+        let mut state = self.state.lock().unwrap();
+        let banks_client = &mut state.context.banks_client;
+        let processed = self
+            .runtime
+            .block_on(banks_client.process_transaction_with_metadata(transaction.clone()))
+            .map_err(bank_error)?;
+        let logs = processed.metadata.map(|metadata| metadata.log_messages).unwrap_or_default();
+        if let Err(err) = processed.result {
+            return Err(ClientError::Rpc(format!("transaction failed: {}\n{}", err, logs.join("\n"))));
+        }
+
+        let slot = self.runtime.block_on(banks_client.get_root_slot()).map_err(bank_error)?;
+        let clock: Clock = self.runtime.block_on(banks_client.get_sysvar()).map_err(bank_error)?;
+        let keys = transaction.message.account_keys.clone();
+        state.known.extend(keys.iter().copied());
+        let signature = transaction.signatures[0];
+        state.transaction_logs.push((keys, TransactionLogs { signature, slot, block_time: Some(clock.unix_timestamp), logs }));
+        Ok(signature)
+    }
+
+    fn simulate_transaction(&self, transaction: &Transaction) -> Result<Simulation> {
+        let mut state = self.state.lock().unwrap();
+        let simulated = self
+            .runtime
+            .block_on(state.context.banks_client.simulate_transaction(transaction.clone()))
+            .map_err(bank_error)?;
+        let details = simulated.simulation_details;
+        Ok(Simulation {
+            err: simulated.result.and_then(|result| result.err()).map(|err| err.to_string()),
+            logs: details.as_ref().map(|details| details.logs.clone()).unwrap_or_default(),
+            units_consumed: details.map(|details| details.units_consumed),
+        })
+    }
+
+    fn get_transaction_logs(&self, address: &Pubkey, until: Option<&Signature>) -> Result<Vec<TransactionLogs>> {
+        let state = self.state.lock().unwrap();
+        let start = until
+            .and_then(|until| state.transaction_logs.iter().position(|(_, logs)| logs.signature == *until))
+            .map_or(0, |position| position + 1);
+        Ok(state.transaction_logs[start..]
+            .iter()
+            .filter(|(keys, _)| keys.contains(address))
+            .map(|(_, logs)| logs.clone())
+            .collect())
+    }
+}
@@ -0,0 +1,18 @@
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("RPC request failed: {0}")]
+    Rpc(String),
+    #[error("Account {0} does not exist")]
+    AccountNotFound(Pubkey),
+    #[error("Account {address} is owned by {owner}, not the SOLCAT program")]
+    InvalidOwner { address: Pubkey, owner: Pubkey },
+    #[error("Account {address} could not be decoded: {reason}")]
+    InvalidAccountData { address: Pubkey, reason: String },
+    #[error("Transaction could not be signed: {0}")]
+    Signing(String),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
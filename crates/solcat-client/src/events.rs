@@ -0,0 +1,35 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_sdk::pubkey::Pubkey;
+
+use solcat_program::events::SolcatEvent;
+
+// Decode the events emitted by the SOLCAT program from transaction logs.
+// Only `Program data:` lines logged while the program itself is executing are read,
+// so events of programs it invokes, or that invoke it, are skipped.
+pub fn parse_logs(logs: &[String]) -> Vec<SolcatEvent> {
+    let mut invocations: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            if invocations.last() != Some(&solcat_program::ID) {
+                continue;
+            }
+            if let Ok(bytes) = STANDARD.decode(data) {
+                events.extend(SolcatEvent::decode(&bytes));
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(id), Some("invoke")) => invocations.push(id.parse().unwrap_or_default()),
+                (Some(_), Some("success" | "failed:")) => {
+                    invocations.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
@@ -0,0 +1,492 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+use solcat_program::state::{AddressReport, BatchReport, GlobalConfig, ListingStatus};
+use solcat_program::{accounts, instruction as args};
+
+use crate::pda;
+
+// Instruction builders for every program instruction.
+// Admin instructions are signed by the program keypair, token accounts come from the fetched GlobalConfig.
+
+fn build(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: solcat_program::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+pub fn initialize(config: GlobalConfig) -> Instruction {
+    build(
+        accounts::Initialize {
+            authority: solcat_program::ID,
+            config: pda::config(),
+            stake_pool: pda::stake_pool(),
+            system_program: system_program::ID,
+        },
+        args::Initialize { config },
+    )
+}
+
+pub fn update_config(config: GlobalConfig) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            authority: solcat_program::ID,
+            config: pda::config(),
+        },
+        args::UpdateConfig { config },
+    )
+}
+
+// The report is a new keypair account that signs alongside the reporter
+pub fn report_address(reporter: &Pubkey, reported_address: &Pubkey, report: &Pubkey, report_args: args::ReportAddress) -> Instruction {
+    build(
+        accounts::ReportAddress {
+            reporter: *reporter,
+            reported_address: *reported_address,
+            report: *report,
+            address_stats: pda::address_stats(reported_address),
+            reporter_stats: pda::reporter_stats(reporter),
+            system_program: system_program::ID,
+        },
+        report_args,
+    )
+}
+
+pub fn mark_suspicious_address(reporter: &Pubkey, reported_address: &Pubkey, reason: String) -> Instruction {
+    build(
+        accounts::MarkSuspiciousAddress {
+            reporter: *reporter,
+            reported_address: *reported_address,
+            report: pda::suspicious_report(reported_address, reporter),
+            address_stats: pda::address_stats(reported_address),
+            reporter_stats: pda::reporter_stats(reporter),
+            system_program: system_program::ID,
+        },
+        args::MarkSuspiciousAddress { reason },
+    )
+}
+
+pub fn unmark_suspicious_address(reporter: &Pubkey, reported_address: &Pubkey) -> Instruction {
+    build(
+        accounts::UnmarkSuspiciousAddress {
+            reporter: *reporter,
+            report: pda::suspicious_report(reported_address, reporter),
+            address_stats: pda::address_stats(reported_address),
+            reporter_stats: pda::reporter_stats(reporter),
+        },
+        args::UnmarkSuspiciousAddress {},
+    )
+}
+
+pub fn update_report(report_key: &Pubkey, report: &AddressReport, risk_score: u8, description: String) -> Instruction {
+    build(
+        accounts::UpdateReport {
+            reporter: report.reporter,
+            report: *report_key,
+            address_stats: pda::address_stats(&report.reported_address),
+        },
+        args::UpdateReport { risk_score, description },
+    )
+}
+
+pub fn stake_on_report(staker: &Pubkey, report_key: &Pubkey, report: &AddressReport, stake_amount: u64) -> Instruction {
+    build(
+        accounts::StakeOnReport {
+            staker: *staker,
+            report: *report_key,
+            address_stats: pda::address_stats(&report.reported_address),
+            reporter_stats: pda::reporter_stats(&report.reporter),
+            config: pda::config(),
+            system_program: system_program::ID,
+        },
+        args::StakeOnReport { stake_amount },
+    )
+}
+
+// A verified report is credited to the reporter through the reward pool
+pub fn update_reporter_stats(reporter: &Pubkey, reputation_score: u8, verified_report: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::UpdateReporterStats {
+            authority: solcat_program::ID,
+            reporter: *reporter,
+            reporter_stats: pda::reporter_stats(reporter),
+            report: verified_report,
+            reward_pool: verified_report.map(|_| pda::reporter_reward_pool()),
+            system_program: system_program::ID,
+        },
+        args::UpdateReporterStats { reputation_score },
+    )
+}
+
+pub fn stake_tokens(
+    config: &GlobalConfig,
+    staker: &Pubkey,
+    staker_token_account: &Pubkey,
+    amount: u64,
+    duration: i64,
+    position: u32,
+) -> Instruction {
+    build(
+        accounts::StakeTokens {
+            staker: *staker,
+            stake_pool: pda::stake_pool(),
+            user_stake: pda::user_stake(staker, position),
+            config: pda::config(),
+            staker_token_account: *staker_token_account,
+            stake_vault: config.stake_vault,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        args::StakeTokens { amount, duration, position },
+    )
+}
+
+pub fn unstake_tokens(staker: &Pubkey, position: u32, amount: u64) -> Instruction {
+    let user_stake = pda::user_stake(staker, position);
+    build(
+        accounts::UnstakeTokens {
+            staker: *staker,
+            stake_pool: pda::stake_pool(),
+            user_stake,
+            unbonding: pda::unbonding(&user_stake),
+            config: pda::config(),
+            system_program: system_program::ID,
+        },
+        args::UnstakeTokens { amount },
+    )
+}
+
+pub fn early_unstake(config: &GlobalConfig, staker: &Pubkey, position: u32, amount: u64) -> Instruction {
+    let user_stake = pda::user_stake(staker, position);
+    build(
+        accounts::EarlyUnstake {
+            staker: *staker,
+            stake_pool: pda::stake_pool(),
+            user_stake,
+            stake_vault: config.stake_vault,
+            unbonding: pda::unbonding(&user_stake),
+            treasury: config.treasury,
+            vault_authority: pda::vault_authority(),
+            config: pda::config(),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        args::EarlyUnstake { amount },
+    )
+}
+
+// The source is the user stake or delegation the tokens were unbonded from
+pub fn withdraw_unbonded(config: &GlobalConfig, staker: &Pubkey, source: &Pubkey, staker_token_account: &Pubkey) -> Instruction {
+    build(
+        accounts::WithdrawUnbonded {
+            staker: *staker,
+            unbonding: pda::unbonding(source),
+            stake_vault: config.stake_vault,
+            staker_token_account: *staker_token_account,
+            vault_authority: pda::vault_authority(),
+            config: pda::config(),
+            token_program: token::ID,
+        },
+        args::WithdrawUnbonded {},
+    )
+}
+
+pub fn slash_unbonding(config: &GlobalConfig, source: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::SlashUnbonding {
+            authority: solcat_program::ID,
+            unbonding: pda::unbonding(source),
+            stake_vault: config.stake_vault,
+            treasury: config.treasury,
+            vault_authority: pda::vault_authority(),
+            config: pda::config(),
+            token_program: token::ID,
+        },
+        args::SlashUnbonding { amount },
+    )
+}
+
+// The refresher is credited through one of their own stake positions
+pub fn refresh_stake_weight(refresher: &Pubkey, refresher_position: u32, user_stake: &Pubkey) -> Instruction {
+    build(
+        accounts::RefreshStakeWeight {
+            refresher: *refresher,
+            stake_pool: pda::stake_pool(),
+            user_stake: *user_stake,
+            refresher_stake: pda::user_stake(refresher, refresher_position),
+        },
+        args::RefreshStakeWeight {},
+    )
+}
+
+pub fn claim_rewards(config: &GlobalConfig, claimer: &Pubkey, position: u32, claimer_token_account: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimRewards {
+            claimer: *claimer,
+            stake_pool: pda::stake_pool(),
+            user_stake: pda::user_stake(claimer, position),
+            treasury: config.treasury,
+            config: pda::config(),
+            claimer_token_account: *claimer_token_account,
+            vault_authority: pda::vault_authority(),
+            token_program: token::ID,
+            reward_pool: pda::reporter_reward_pool(),
+        },
+        args::ClaimRewards {},
+    )
+}
+
+pub fn compound_rewards(config: &GlobalConfig, staker: &Pubkey, position: u32) -> Instruction {
+    build(
+        accounts::CompoundRewards {
+            staker: *staker,
+            stake_pool: pda::stake_pool(),
+            user_stake: pda::user_stake(staker, position),
+            treasury: config.treasury,
+            stake_vault: config.stake_vault,
+            vault_authority: pda::vault_authority(),
+            config: pda::config(),
+            token_program: token::ID,
+            reward_pool: pda::reporter_reward_pool(),
+        },
+        args::CompoundRewards {},
+    )
+}
+
+pub fn distribute_rewards(config: &GlobalConfig) -> Instruction {
+    build(
+        accounts::DistributeRewards {
+            authority: solcat_program::ID,
+            stake_pool: pda::stake_pool(),
+            config: pda::config(),
+            treasury: config.treasury,
+            reward_pool: pda::reporter_reward_pool(),
+        },
+        args::DistributeRewards {},
+    )
+}
+
+// The batch report is a new keypair account that signs alongside the reporter
+pub fn submit_batch_report(reporter: &Pubkey, batch_report: &Pubkey, addresses: Vec<Pubkey>, risk_scores: Vec<u8>) -> Instruction {
+    build(
+        accounts::SubmitBatchReport {
+            reporter: *reporter,
+            batch_report: *batch_report,
+            reporter_stats: pda::reporter_stats(reporter),
+            system_program: system_program::ID,
+        },
+        args::SubmitBatchReport { addresses, risk_scores },
+    )
+}
+
+pub fn verify_batch_report(verifier: &Pubkey, batch_key: &Pubkey, batch: &BatchReport, is_verified: bool) -> Instruction {
+    build(
+        accounts::VerifyBatchReport {
+            verifier: *verifier,
+            batch_report: *batch_key,
+            verifier_stats: pda::reporter_stats(verifier),
+            reporter_stats: pda::reporter_stats(&batch.reporter),
+            reward_pool: is_verified.then(pda::reporter_reward_pool),
+        },
+        args::VerifyBatchReport { is_verified },
+    )
+}
+
+fn apply_listing(address: &Pubkey) -> accounts::ApplyListing {
+    accounts::ApplyListing {
+        authority: solcat_program::ID,
+        address: *address,
+        report_history: pda::report_history(address),
+        system_program: system_program::ID,
+    }
+}
+
+pub fn blacklist_address(address: &Pubkey, reason: String, expiry: i64) -> Instruction {
+    build(apply_listing(address), args::BlacklistAddress { reason, expiry })
+}
+
+pub fn set_listing_status(address: &Pubkey, status: ListingStatus, reason: String, expiry: i64) -> Instruction {
+    build(apply_listing(address), args::SetListingStatus { status, reason, expiry })
+}
+
+pub fn remove_from_blacklist(address: &Pubkey, rationale: String) -> Instruction {
+    build(
+        accounts::RemoveFromBlacklist {
+            authority: solcat_program::ID,
+            report_history: pda::report_history(address),
+        },
+        args::RemoveFromBlacklist { rationale },
+    )
+}
+
+pub fn evaluate_blacklist(payer: &Pubkey, address: &Pubkey) -> Instruction {
+    build(
+        accounts::EvaluateBlacklist {
+            address_stats: pda::address_stats(address),
+            payer: *payer,
+            report_history: pda::report_history(address),
+            config: pda::config(),
+            system_program: system_program::ID,
+        },
+        args::EvaluateBlacklist {},
+    )
+}
+
+pub fn update_history(payer: &Pubkey, report_key: &Pubkey, report: &AddressReport) -> Instruction {
+    build(
+        accounts::UpdateHistory {
+            report: *report_key,
+            payer: *payer,
+            report_history: pda::report_history(&report.reported_address),
+            system_program: system_program::ID,
+        },
+        args::UpdateHistory {},
+    )
+}
+
+pub fn check_address(address: &Pubkey) -> Instruction {
+    build(
+        accounts::CheckAddress {
+            address: *address,
+            address_stats: pda::address_stats(address),
+            report_history: pda::report_history(address),
+        },
+        args::CheckAddress {},
+    )
+}
+
+// Every destination is passed as its (address, stats, history) triple
+pub fn assert_low_risk(destinations: &[Pubkey], max_score: u8) -> Instruction {
+    let mut instruction = build(accounts::AssertLowRisk {}, args::AssertLowRisk { max_score });
+    for address in destinations {
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(*address, false),
+            AccountMeta::new_readonly(pda::address_stats(address), false),
+            AccountMeta::new_readonly(pda::report_history(address), false),
+        ]);
+    }
+    instruction
+}
+
+pub fn fund_reporter_rewards(config: &GlobalConfig, amount: u64) -> Instruction {
+    build(
+        accounts::FundReporterRewards {
+            authority: solcat_program::ID,
+            reward_pool: pda::reporter_reward_pool(),
+            stake_pool: pda::stake_pool(),
+            treasury: config.treasury,
+            config: pda::config(),
+            system_program: system_program::ID,
+        },
+        args::FundReporterRewards { amount },
+    )
+}
+
+pub fn claim_reporter_rewards(config: &GlobalConfig, reporter: &Pubkey, reporter_token_account: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimReporterRewards {
+            reporter: *reporter,
+            reporter_stats: pda::reporter_stats(reporter),
+            reward_pool: pda::reporter_reward_pool(),
+            treasury: config.treasury,
+            reporter_token_account: *reporter_token_account,
+            vault_authority: pda::vault_authority(),
+            config: pda::config(),
+            token_program: token::ID,
+        },
+        args::ClaimReporterRewards {},
+    )
+}
+
+pub fn delegate_stake(
+    config: &GlobalConfig,
+    delegator: &Pubkey,
+    reporter: &Pubkey,
+    delegator_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::DelegateStake {
+            delegator: *delegator,
+            reporter: *reporter,
+            reporter_stats: pda::reporter_stats(reporter),
+            delegation: pda::delegation(reporter, delegator),
+            delegator_token_account: *delegator_token_account,
+            stake_vault: config.stake_vault,
+            config: pda::config(),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        args::DelegateStake { amount },
+    )
+}
+
+pub fn undelegate_stake(delegator: &Pubkey, reporter: &Pubkey, shares: u64) -> Instruction {
+    let delegation = pda::delegation(reporter, delegator);
+    build(
+        accounts::UndelegateStake {
+            delegator: *delegator,
+            delegation,
+            reporter_stats: pda::reporter_stats(reporter),
+            unbonding: pda::unbonding(&delegation),
+            config: pda::config(),
+            system_program: system_program::ID,
+        },
+        args::UndelegateStake { shares },
+    )
+}
+
+pub fn set_commission(reporter: &Pubkey, commission_bps: u16) -> Instruction {
+    build(
+        accounts::SetCommission {
+            reporter: *reporter,
+            reporter_stats: pda::reporter_stats(reporter),
+        },
+        args::SetCommission { commission_bps },
+    )
+}
+
+pub fn claim_delegation_rewards(
+    config: &GlobalConfig,
+    delegator: &Pubkey,
+    reporter: &Pubkey,
+    delegator_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ClaimDelegationRewards {
+            delegator: *delegator,
+            delegation: pda::delegation(reporter, delegator),
+            reporter_stats: pda::reporter_stats(reporter),
+            reward_pool: pda::reporter_reward_pool(),
+            treasury: config.treasury,
+            delegator_token_account: *delegator_token_account,
+            vault_authority: pda::vault_authority(),
+            config: pda::config(),
+            token_program: token::ID,
+        },
+        args::ClaimDelegationRewards {},
+    )
+}
+
+// The reporter's own delegation bears the commission share of the slash
+pub fn slash_delegation(config: &GlobalConfig, reporter: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::SlashDelegation {
+            authority: solcat_program::ID,
+            reporter: *reporter,
+            reporter_stats: pda::reporter_stats(reporter),
+            reporter_delegation: pda::delegation(reporter, reporter),
+            stake_vault: config.stake_vault,
+            treasury: config.treasury,
+            vault_authority: pda::vault_authority(),
+            config: pda::config(),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        args::SlashDelegation { amount },
+    )
+}
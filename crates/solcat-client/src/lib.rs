@@ -0,0 +1,510 @@
+// Client for the SOLCAT program: typed account fetchers, PDA derivation,
+// instruction builders and transaction helpers over a pluggable RPC.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_sdk::account::{from_account, Account};
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+
+use solcat_program::state::{
+    AddressReport, AddressRiskSummary, AddressStats, BatchReport, Delegation, GlobalConfig,
+    ReportHistory, ReporterRewardPool, ReporterStats, StakePool, Unbonding, UserStake,
+};
+
+#[cfg(feature = "bank")]
+pub mod bank;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod names;
+pub mod pda;
+pub mod rpc;
+pub mod stub;
+
+#[cfg(feature = "bank")]
+pub use bank::BankRpc;
+pub use error::{ClientError, Result};
+pub use rpc::{Simulation, SolcatRpc, TransactionLogs};
+pub use stub::AccountStubRpc;
+pub use solcat_program;
+
+// Decode a program account as returned by RPC, checking its owner and discriminator
+pub fn decode_account<T: AccountDeserialize>(address: &Pubkey, account: &Account) -> Result<T> {
+    if account.owner != solcat_program::ID {
+        return Err(ClientError::InvalidOwner { address: *address, owner: account.owner });
+    }
+    T::try_deserialize(&mut account.data.as_slice()).map_err(|err| ClientError::InvalidAccountData {
+        address: *address,
+        reason: err.to_string(),
+    })
+}
+
+pub struct SolcatClient<R> {
+    rpc: R,
+}
+
+impl<R: SolcatRpc> SolcatClient<R> {
+    pub fn new(rpc: R) -> Self {
+        Self { rpc }
+    }
+
+    pub fn rpc(&self) -> &R {
+        &self.rpc
+    }
+
+    // Fetch a program account, None if it does not exist
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        self.rpc
+            .get_account(address)?
+            .map(|account| decode_account(address, &account))
+            .transpose()
+    }
+
+    // Fetch a program account that must exist
+    pub fn fetch_required<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        self.fetch(address)?.ok_or(ClientError::AccountNotFound(*address))
+    }
+
+    // Every program account of one type, matched by discriminator
+    pub fn fetch_all<T: AccountDeserialize + Discriminator>(&self) -> Result<Vec<(Pubkey, T)>> {
+        self.rpc
+            .get_program_accounts(&solcat_program::ID)?
+            .into_iter()
+            .filter(|(_, account)| account.data.starts_with(&T::DISCRIMINATOR))
+            .map(|(address, account)| Ok((address, decode_account(&address, &account)?)))
+            .collect()
+    }
+
+    pub fn config(&self) -> Result<GlobalConfig> {
+        self.fetch_required(&pda::config())
+    }
+
+    pub fn stake_pool(&self) -> Result<StakePool> {
+        self.fetch_required(&pda::stake_pool())
+    }
+
+    pub fn reporter_reward_pool(&self) -> Result<Option<ReporterRewardPool>> {
+        self.fetch(&pda::reporter_reward_pool())
+    }
+
+    pub fn report(&self, report: &Pubkey) -> Result<AddressReport> {
+        self.fetch_required(report)
+    }
+
+    pub fn batch_report(&self, batch_report: &Pubkey) -> Result<BatchReport> {
+        self.fetch_required(batch_report)
+    }
+
+    pub fn address_stats(&self, address: &Pubkey) -> Result<Option<AddressStats>> {
+        self.fetch(&pda::address_stats(address))
+    }
+
+    pub fn report_history(&self, address: &Pubkey) -> Result<Option<ReportHistory>> {
+        self.fetch(&pda::report_history(address))
+    }
+
+    pub fn reporter_stats(&self, reporter: &Pubkey) -> Result<Option<ReporterStats>> {
+        self.fetch(&pda::reporter_stats(reporter))
+    }
+
+    pub fn user_stake(&self, owner: &Pubkey, position: u32) -> Result<Option<UserStake>> {
+        self.fetch(&pda::user_stake(owner, position))
+    }
+
+    pub fn unbonding(&self, source: &Pubkey) -> Result<Option<Unbonding>> {
+        self.fetch(&pda::unbonding(source))
+    }
+
+    pub fn delegation(&self, reporter: &Pubkey, delegator: &Pubkey) -> Result<Option<Delegation>> {
+        self.fetch(&pda::delegation(reporter, delegator))
+    }
+
+    // Cluster time as the program sees it, from the clock sysvar
+    pub fn unix_timestamp(&self) -> Result<i64> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?.ok_or(ClientError::AccountNotFound(sysvar::clock::ID))?;
+        let clock: Clock = from_account(&account).ok_or_else(|| ClientError::InvalidAccountData {
+            address: sysvar::clock::ID,
+            reason: "not a clock sysvar".to_string(),
+        })?;
+        Ok(clock.unix_timestamp)
+    }
+
+    // The summary check_address would return at `now`, read without a transaction
+    pub fn risk_summary(&self, address: &Pubkey, now: i64) -> Result<AddressRiskSummary> {
+        let keys = [pda::address_stats(address), pda::report_history(address)];
+        let accounts = self.rpc.get_multiple_accounts(&keys)?;
+
+        // Accounts the program does not own yet read as missing, as on chain
+        let owned = |index: usize| accounts[index].as_ref().filter(|account| account.owner == solcat_program::ID);
+        let stats: Option<AddressStats> = owned(0).map(|account| decode_account(&keys[0], account)).transpose()?;
+        let history: Option<ReportHistory> = owned(1).map(|account| decode_account(&keys[1], account)).transpose()?;
+
+        Ok(AddressRiskSummary::new(*address, stats.as_ref(), history.as_ref(), now))
+    }
+
+    // Build and sign a transaction against the latest blockhash, the payer must be among the signers
+    pub fn transaction(&self, instructions: &[Instruction], payer: &Pubkey, signers: &[&dyn Signer]) -> Result<Transaction> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let mut transaction = Transaction::new_with_payer(instructions, Some(payer));
+        transaction
+            .try_sign(signers, blockhash)
+            .map_err(|err| ClientError::Signing(err.to_string()))?;
+        Ok(transaction)
+    }
+
+    pub fn send(&self, instructions: &[Instruction], payer: &Pubkey, signers: &[&dyn Signer]) -> Result<Signature> {
+        let transaction = self.transaction(instructions, payer, signers)?;
+        self.rpc.send_transaction(&transaction)
+    }
+
+    pub fn simulate(&self, instructions: &[Instruction], payer: &Pubkey, signers: &[&dyn Signer]) -> Result<Simulation> {
+        let transaction = self.transaction(instructions, payer, signers)?;
+        self.rpc.simulate_transaction(&transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::Event;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use solana_sdk::signature::Keypair;
+
+    use solcat_program::events::{ReportUpdated, SolcatEvent};
+    use solcat_program::calculate_risk_score;
+    use solcat_program::state::{
+        BlacklistAction, BlacklistRecord, EmissionDecay, REPORT_HISTORY_SPACE, EmissionSchedule, ListingStatus, ListingThreshold,
+        ListingThresholds, PenaltyDestination, RiskMetrics, RiskType, VerificationStatus,
+    };
+
+    use super::*;
+
+    fn stats(address: Pubkey) -> AddressStats {
+        AddressStats {
+            address,
+            total_reports: 2,
+            risk_scores: vec![80, 40],
+            risk_score_times: vec![0, 0],
+            total_stake: 5_000,
+            last_update: 0,
+            last_report_time: 0,
+            weighted_risk_score: 80 * 300 + 40 * 100,
+            total_vote_weight: 400,
+            reporters: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            total_confidence: 150,
+        }
+    }
+
+    fn reporter_stats(reputation_score: u8, total_stake: u64) -> ReporterStats {
+        ReporterStats {
+            total_reports: 0,
+            successful_reports: 0,
+            total_stake,
+            quick_flag_stake: 0,
+            reputation_score,
+            last_report_time: 0,
+            reports_in_window: 0,
+            cooldown_end_time: 0,
+            token_balance: 0,
+            rewards_claimed: 0,
+            reward_points: 0,
+            points_epoch: 0,
+            delegated_stake: 0,
+            delegation_shares: 0,
+            commission_bps: 0,
+            delegation_reward_per_share: 0,
+        }
+    }
+
+    fn report_args(risk_score: u8) -> solcat_program::instruction::ReportAddress {
+        solcat_program::instruction::ReportAddress {
+            risk_score,
+            description: "Drains approvals".to_string(),
+            risk_types: vec![RiskType::Scam],
+            confidence_score: 90,
+            evidence_count: 3,
+            risk_metrics: RiskMetrics {
+                transaction_volume: 500_000_000_000,
+                unique_interactions: 1_000,
+                age_of_account: 0,
+                suspicious_patterns: vec!["drainer".to_string()],
+            },
+        }
+    }
+
+    // Lists an address at `Block` once a single reporter scores it 50 or more
+    fn config() -> GlobalConfig {
+        GlobalConfig {
+            min_stake_amount: 1_000_000,
+            emission_schedule: EmissionSchedule {
+                start_time: 0,
+                end_time: 0,
+                epoch_duration: 86_400,
+                initial_epoch_emission: 0,
+                decay: EmissionDecay::Constant,
+                decay_param: 0,
+            },
+            token_mint: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            total_supply: 0,
+            circulating_supply: 0,
+            staking_enabled: false,
+            min_lock_duration: 0,
+            listing_thresholds: ListingThresholds {
+                block: Some(ListingThreshold { min_aggregate_score: 50, min_distinct_reporters: 1, min_total_stake: 0 }),
+                ..ListingThresholds::default()
+            },
+            stake_vault: Pubkey::new_unique(),
+            early_unstake_penalty_bps: 0,
+            early_unstake_penalty_destination: PenaltyDestination::Treasury,
+            unbonding_period: 0,
+        }
+    }
+
+    // A funded reporter whose stats allow full reports
+    fn bank_reporter(bank: &BankRpc) -> Keypair {
+        let reporter = Keypair::new();
+        bank.airdrop(&reporter.pubkey(), 10_000_000_000);
+        bank.set_program_account(pda::reporter_stats(&reporter.pubkey()), &reporter_stats(50, 0));
+        reporter
+    }
+
+    #[test]
+    fn fetches_program_accounts_by_type() {
+        let rpc = AccountStubRpc::new();
+        let address = Pubkey::new_unique();
+        rpc.set_program_account(pda::address_stats(&address), &stats(address));
+        let client = SolcatClient::new(&rpc);
+
+        let fetched = client.address_stats(&address).unwrap().unwrap();
+        assert_eq!(fetched.address, address);
+        assert_eq!(fetched.aggregate_score(), 70);
+        assert!(client.address_stats(&Pubkey::new_unique()).unwrap().is_none());
+        assert_eq!(client.fetch_all::<AddressStats>().unwrap().len(), 1);
+
+        // The stats do not decode as a different account type
+        assert!(matches!(
+            client.fetch::<ReportHistory>(&pda::address_stats(&address)),
+            Err(ClientError::InvalidAccountData { .. })
+        ));
+    }
+
+    #[test]
+    fn risk_summary_matches_missing_and_existing_accounts() {
+        let rpc = AccountStubRpc::new();
+        let address = Pubkey::new_unique();
+        let client = SolcatClient::new(&rpc);
+
+        let empty = client.risk_summary(&address, 0).unwrap();
+        assert_eq!(empty, AddressRiskSummary::new(address, None, None, 0));
+        assert_eq!(empty.listing_status, ListingStatus::None);
+
+        rpc.set_program_account(pda::address_stats(&address), &stats(address));
+        let summary = client.risk_summary(&address, 0).unwrap();
+        assert_eq!(summary.aggregate_score, 70);
+        assert_eq!(summary.confidence, 75);
+        assert_eq!(summary.distinct_reporters, 2);
+    }
+
+    #[test]
+    fn sends_signed_instructions() {
+        let rpc = AccountStubRpc::new();
+        let client = SolcatClient::new(&rpc);
+        let reporter = Keypair::new();
+        let reported = Pubkey::new_unique();
+
+        let instruction = instructions::mark_suspicious_address(&reporter.pubkey(), &reported, "drainer".to_string());
+        assert_eq!(instruction.accounts[2].pubkey, pda::suspicious_report(&reported, &reporter.pubkey()));
+
+        let signature = client.send(&[instruction], &reporter.pubkey(), &[&reporter]).unwrap();
+        let sent = rpc.transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].signatures[0], signature);
+
+        // A missing signer fails before anything is sent
+        let other = Keypair::new();
+        let instruction = instructions::unmark_suspicious_address(&other.pubkey(), &reported);
+        assert!(matches!(
+            client.send(&[instruction], &other.pubkey(), &[&reporter]),
+            Err(ClientError::Signing(_))
+        ));
+        assert_eq!(rpc.transactions().len(), 1);
+    }
+
+    #[test]
+    fn reports_and_updates_run_through_the_program() {
+        let bank = BankRpc::new();
+        let client = SolcatClient::new(&bank);
+        let reporter = bank_reporter(&bank);
+
+        let reported = Pubkey::new_unique();
+        let report = Keypair::new();
+        let instruction = instructions::report_address(
+            &reporter.pubkey(),
+            &reported,
+            &report.pubkey(),
+            report_args(60),
+        );
+        client.send(&[instruction], &reporter.pubkey(), &[&reporter, &report]).unwrap();
+
+        let stored = client.report(&report.pubkey()).unwrap();
+        assert_eq!(stored.reported_address, reported);
+        assert_eq!(stored.risk_assessment.base_score, 60);
+        let stats = client.address_stats(&reported).unwrap().unwrap();
+        assert_eq!(stats.total_reports, 1);
+        assert_eq!(stats.weighted_risk_score, 60 * u128::from(stored.vote_weight));
+        let reporter_stats: ReporterStats = client.fetch_required(&pda::reporter_stats(&reporter.pubkey())).unwrap();
+        assert_eq!(reporter_stats.total_reports, 1);
+
+        // The time lock holds the update back until it ends
+        let early = instructions::update_report(&report.pubkey(), &stored, 85, "Too early".to_string());
+        assert!(client.send(&[early], &reporter.pubkey(), &[&reporter]).is_err());
+
+        bank.set_clock(stored.time_lock_end);
+        let update = instructions::update_report(&report.pubkey(), &stored, 85, "Confirmed drainer".to_string());
+        let signature = client.send(&[update], &reporter.pubkey(), &[&reporter]).unwrap();
+
+        let updated = client.report(&report.pubkey()).unwrap();
+        assert_eq!(updated.risk_assessment.base_score, 85);
+        assert_eq!(updated.description, "Confirmed drainer");
+        assert_eq!(updated.risk_score, calculate_risk_score(&updated.risk_assessment, &updated.risk_metrics));
+        let stats = client.address_stats(&reported).unwrap().unwrap();
+        assert_eq!(stats.weighted_risk_score, 85 * u128::from(stored.vote_weight));
+
+        // Only the successful transactions are recorded for the report
+        let logs = bank.get_transaction_logs(&report.pubkey(), None).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[1].signature, signature);
+        assert!(logs[1].logs.contains(&format!("Program {} success", solcat_program::ID)));
+    }
+
+    #[test]
+    fn only_a_new_report_undoes_a_removal() {
+        let bank = BankRpc::new();
+        let client = SolcatClient::new(&bank);
+        bank.set_program_account(pda::config(), &config());
+        let reporter = bank_reporter(&bank);
+        let reported = Pubkey::new_unique();
+        let report = Keypair::new();
+        let instruction = instructions::report_address(&reporter.pubkey(), &reported, &report.pubkey(), report_args(80));
+        client.send(&[instruction], &reporter.pubkey(), &[&reporter, &report]).unwrap();
+        let stored = client.report(&report.pubkey()).unwrap();
+
+        // The listing was lifted by hand once the report's time lock ended
+        let removed_at = stored.time_lock_end;
+        let mut history = ReportHistory::new(reported);
+        history.push_listing_change(BlacklistRecord {
+            action: BlacklistAction::Unblacklist,
+            status: ListingStatus::None,
+            authority: solcat_program::ID,
+            reason: "false positive".to_string(),
+            timestamp: removed_at,
+            expiry: 0,
+        });
+        bank.set_program_account_with_space(pda::report_history(&reported), &history, 8 + REPORT_HISTORY_SPACE);
+        bank.set_clock(removed_at + 1);
+
+        // Updating and staking on the old report leave the removal standing
+        let update = instructions::update_report(&report.pubkey(), &stored, 90, "Still draining".to_string());
+        let stake = instructions::stake_on_report(&reporter.pubkey(), &report.pubkey(), &stored, 1_000_000);
+        client.send(&[update, stake], &reporter.pubkey(), &[&reporter]).unwrap();
+        let evaluate = instructions::evaluate_blacklist(&reporter.pubkey(), &reported);
+        assert!(matches!(
+            client.send(&[evaluate], &reporter.pubkey(), &[&reporter]),
+            Err(ClientError::Rpc(err)) if err.contains("InvalidBlacklistOperation")
+        ));
+
+        let other = bank_reporter(&bank);
+        let report = Keypair::new();
+        let instruction = instructions::report_address(&other.pubkey(), &reported, &report.pubkey(), report_args(70));
+        client.send(&[instruction], &other.pubkey(), &[&other, &report]).unwrap();
+        let evaluate = instructions::evaluate_blacklist(&other.pubkey(), &reported);
+        client.send(&[evaluate], &other.pubkey(), &[&other]).unwrap();
+        let history: ReportHistory = client.fetch_required(&pda::report_history(&reported)).unwrap();
+        assert_eq!(history.listing_status, ListingStatus::Block);
+    }
+
+    #[test]
+    fn verifiers_need_report_stake_and_reputation() {
+        let bank = BankRpc::new();
+        let client = SolcatClient::new(&bank);
+        let verifier = Keypair::new();
+        bank.airdrop(&verifier.pubkey(), 1_000_000_000);
+        let reporter = Pubkey::new_unique();
+        bank.set_program_account(pda::reporter_stats(&reporter), &reporter_stats(50, 0));
+        let batch_key = Pubkey::new_unique();
+        let batch = BatchReport {
+            reporter,
+            addresses: vec![Pubkey::new_unique()],
+            risk_scores: vec![80],
+            timestamp: 0,
+            verification_status: VerificationStatus::Pending,
+        };
+        bank.set_program_account(batch_key, &batch);
+        bank.set_program_account(
+            pda::reporter_reward_pool(),
+            &ReporterRewardPool {
+                epoch: 0,
+                epoch_start: 0,
+                epoch_budget: 0,
+                epoch_points: 0,
+                closed_epochs: Vec::new(),
+                total_funded: 0,
+                total_claimed: 0,
+            },
+        );
+        // Each attempt has its own fee payer, a resent transaction would be a duplicate
+        let verify = |payer: &Keypair| {
+            let instruction = instructions::verify_batch_report(&verifier.pubkey(), &batch_key, &batch, true);
+            client.send(&[instruction], &payer.pubkey(), &[payer, &verifier])
+        };
+
+        // Stake held by quick flags does not qualify a verifier
+        let mut stats = reporter_stats(50, 200_000_000);
+        stats.quick_flag_stake = 150_000_000;
+        bank.set_program_account(pda::reporter_stats(&verifier.pubkey()), &stats);
+        assert!(matches!(verify(&verifier), Err(ClientError::Rpc(err)) if err.contains("InsufficientStake")));
+
+        // Neither does stake without reputation
+        bank.set_program_account(pda::reporter_stats(&verifier.pubkey()), &reporter_stats(10, 200_000_000));
+        assert!(matches!(verify(&bank.payer()), Err(ClientError::Rpc(err)) if err.contains("InsufficientReputation")));
+
+        bank.set_program_account(pda::reporter_stats(&verifier.pubkey()), &reporter_stats(50, 200_000_000));
+        let payer = Keypair::new();
+        bank.airdrop(&payer.pubkey(), 1_000_000_000);
+        verify(&payer).unwrap();
+        let verified: BatchReport = client.fetch_required(&batch_key).unwrap();
+        assert_eq!(verified.verification_status, VerificationStatus::Verified);
+        let reporter_stats: ReporterStats = client.fetch_required(&pda::reporter_stats(&reporter)).unwrap();
+        assert!(reporter_stats.reward_points > 0);
+    }
+
+    #[test]
+    fn parses_events_of_the_program_only() {
+        let event = ReportUpdated {
+            report: Pubkey::new_unique(),
+            reporter: Pubkey::new_unique(),
+            reported_address: Pubkey::new_unique(),
+            risk_score: 60,
+            timestamp: 1,
+        };
+        let data = format!("Program data: {}", STANDARD.encode(event.data()));
+        let other = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {} invoke [1]", solcat_program::ID),
+            data.clone(),
+            format!("Program {} invoke [2]", other),
+            data.clone(),
+            format!("Program {} success", other),
+            format!("Program {} success", solcat_program::ID),
+            data,
+        ];
+
+        assert_eq!(events::parse_logs(&logs), vec![SolcatEvent::ReportUpdated(event)]);
+    }
+}
@@ -0,0 +1,103 @@
+use solcat_program::state::{BlacklistAction, EmissionDecay, ListingStatus, PenaltyDestination, RiskType, VerificationStatus};
+
+// Stable names of program enums, shared by the CLI, feed importers and exporters
+
+pub const RISK_TYPES: [RiskType; 7] = [
+    RiskType::Scam,
+    RiskType::Phishing,
+    RiskType::Malware,
+    RiskType::Ransomware,
+    RiskType::MoneyLaundering,
+    RiskType::MarketManipulation,
+    RiskType::Unknown,
+];
+
+pub fn risk_type_name(risk_type: &RiskType) -> &'static str {
+    match risk_type {
+        RiskType::Scam => "scam",
+        RiskType::Phishing => "phishing",
+        RiskType::Malware => "malware",
+        RiskType::Ransomware => "ransomware",
+        RiskType::MoneyLaundering => "money_laundering",
+        RiskType::MarketManipulation => "market_manipulation",
+        RiskType::Unknown => "unknown",
+    }
+}
+
+// Accepts names in any case, with `-`, `_` or spaces between words
+pub fn parse_risk_type(name: &str) -> Option<RiskType> {
+    let name = normalize(name);
+    RISK_TYPES.into_iter().find(|risk_type| risk_type_name(risk_type) == name)
+}
+
+pub const LISTING_STATUSES: [ListingStatus; 5] = [
+    ListingStatus::None,
+    ListingStatus::Watch,
+    ListingStatus::Warn,
+    ListingStatus::Block,
+    ListingStatus::Sanctioned,
+];
+
+pub fn listing_status_name(status: &ListingStatus) -> &'static str {
+    match status {
+        ListingStatus::None => "none",
+        ListingStatus::Watch => "watch",
+        ListingStatus::Warn => "warn",
+        ListingStatus::Block => "block",
+        ListingStatus::Sanctioned => "sanctioned",
+    }
+}
+
+pub fn parse_listing_status(name: &str) -> Option<ListingStatus> {
+    let name = normalize(name);
+    LISTING_STATUSES.into_iter().find(|status| listing_status_name(status) == name)
+}
+
+pub fn verification_status_name(status: &VerificationStatus) -> &'static str {
+    match status {
+        VerificationStatus::Pending => "pending",
+        VerificationStatus::Verified => "verified",
+        VerificationStatus::Rejected => "rejected",
+    }
+}
+
+pub fn blacklist_action_name(action: &BlacklistAction) -> &'static str {
+    match action {
+        BlacklistAction::Blacklist => "blacklist",
+        BlacklistAction::Amend => "amend",
+        BlacklistAction::Unblacklist => "unblacklist",
+    }
+}
+
+pub const PENALTY_DESTINATIONS: [PenaltyDestination; 2] = [PenaltyDestination::Treasury, PenaltyDestination::Stakers];
+
+pub fn penalty_destination_name(destination: &PenaltyDestination) -> &'static str {
+    match destination {
+        PenaltyDestination::Treasury => "treasury",
+        PenaltyDestination::Stakers => "stakers",
+    }
+}
+
+pub fn parse_penalty_destination(name: &str) -> Option<PenaltyDestination> {
+    let name = normalize(name);
+    PENALTY_DESTINATIONS.into_iter().find(|destination| penalty_destination_name(destination) == name)
+}
+
+pub const EMISSION_DECAYS: [EmissionDecay; 3] = [EmissionDecay::Constant, EmissionDecay::Halving, EmissionDecay::Linear];
+
+pub fn emission_decay_name(decay: &EmissionDecay) -> &'static str {
+    match decay {
+        EmissionDecay::Constant => "constant",
+        EmissionDecay::Halving => "halving",
+        EmissionDecay::Linear => "linear",
+    }
+}
+
+pub fn parse_emission_decay(name: &str) -> Option<EmissionDecay> {
+    let name = normalize(name);
+    EMISSION_DECAYS.into_iter().find(|decay| emission_decay_name(decay) == name)
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_ascii_lowercase().replace(['-', ' '], "_")
+}
@@ -0,0 +1,56 @@
+use solana_sdk::pubkey::Pubkey;
+
+use solcat_program::state::{
+    find_address_stats_address, find_config_address, find_delegation_address,
+    find_report_history_address, find_reporter_reward_pool_address, find_reporter_stats_address,
+    find_stake_pool_address, find_suspicious_address, find_unbonding_address,
+    find_user_stake_address, find_vault_authority_address,
+};
+
+// Program derived addresses of the deployed program, bumps are left to the program
+
+pub fn config() -> Pubkey {
+    find_config_address(&solcat_program::ID).0
+}
+
+pub fn stake_pool() -> Pubkey {
+    find_stake_pool_address(&solcat_program::ID).0
+}
+
+pub fn vault_authority() -> Pubkey {
+    find_vault_authority_address(&solcat_program::ID).0
+}
+
+pub fn reporter_reward_pool() -> Pubkey {
+    find_reporter_reward_pool_address(&solcat_program::ID).0
+}
+
+pub fn address_stats(address: &Pubkey) -> Pubkey {
+    find_address_stats_address(&solcat_program::ID, address).0
+}
+
+pub fn report_history(address: &Pubkey) -> Pubkey {
+    find_report_history_address(&solcat_program::ID, address).0
+}
+
+pub fn reporter_stats(reporter: &Pubkey) -> Pubkey {
+    find_reporter_stats_address(&solcat_program::ID, reporter).0
+}
+
+// The report a quick flag of mark_suspicious_address is stored at
+pub fn suspicious_report(address: &Pubkey, reporter: &Pubkey) -> Pubkey {
+    find_suspicious_address(&solcat_program::ID, address, reporter).0
+}
+
+pub fn user_stake(owner: &Pubkey, position: u32) -> Pubkey {
+    find_user_stake_address(&solcat_program::ID, owner, position).0
+}
+
+// Unbonding record of a user stake or delegation
+pub fn unbonding(source: &Pubkey) -> Pubkey {
+    find_unbonding_address(&solcat_program::ID, source).0
+}
+
+pub fn delegation(reporter: &Pubkey, delegator: &Pubkey) -> Pubkey {
+    find_delegation_address(&solcat_program::ID, reporter, delegator).0
+}
@@ -0,0 +1,176 @@
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+
+use crate::error::Result;
+
+// Outcome of a simulated transaction
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Simulation {
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+// Logs of a successful transaction that invoked the program
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionLogs {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub logs: Vec<String>,
+}
+
+// The RPC calls the client is built on, implemented for RpcClient, and by BankRpc and AccountStubRpc for tests
+pub trait SolcatRpc {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>>;
+
+    fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        addresses.iter().map(|address| self.get_account(address)).collect()
+    }
+
+    fn get_program_accounts(&self, program_id: &Pubkey) -> Result<Vec<(Pubkey, Account)>>;
+
+    fn get_slot(&self) -> Result<u64>;
+
+    fn get_latest_blockhash(&self) -> Result<Hash>;
+
+    // Sends the transaction and waits for it to be confirmed
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature>;
+
+    fn simulate_transaction(&self, transaction: &Transaction) -> Result<Simulation>;
+
+    // Logs of the successful transactions that mentioned `address` after `until`, oldest first
+    fn get_transaction_logs(&self, address: &Pubkey, until: Option<&Signature>) -> Result<Vec<TransactionLogs>>;
+}
+
+impl<T: SolcatRpc + ?Sized> SolcatRpc for &T {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        (**self).get_account(address)
+    }
+
+    fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        (**self).get_multiple_accounts(addresses)
+    }
+
+    fn get_program_accounts(&self, program_id: &Pubkey) -> Result<Vec<(Pubkey, Account)>> {
+        (**self).get_program_accounts(program_id)
+    }
+
+    fn get_slot(&self) -> Result<u64> {
+        (**self).get_slot()
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash> {
+        (**self).get_latest_blockhash()
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        (**self).send_transaction(transaction)
+    }
+
+    fn simulate_transaction(&self, transaction: &Transaction) -> Result<Simulation> {
+        (**self).simulate_transaction(transaction)
+    }
+
+    fn get_transaction_logs(&self, address: &Pubkey, until: Option<&Signature>) -> Result<Vec<TransactionLogs>> {
+        (**self).get_transaction_logs(address, until)
+    }
+}
+
+#[cfg(feature = "rpc-client")]
+mod rpc_client {
+    use solana_client::client_error::ClientError as RpcClientError;
+    use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+    use solana_client::rpc_config::RpcTransactionConfig;
+    use solana_transaction_status::UiTransactionEncoding;
+
+    use super::*;
+    use crate::error::ClientError;
+
+    fn rpc_error(err: RpcClientError) -> ClientError {
+        ClientError::Rpc(err.to_string())
+    }
+
+    impl SolcatRpc for RpcClient {
+        fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+            self.get_account_with_commitment(address, self.commitment())
+                .map(|response| response.value)
+                .map_err(rpc_error)
+        }
+
+        fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+            RpcClient::get_multiple_accounts(self, addresses).map_err(rpc_error)
+        }
+
+        fn get_program_accounts(&self, program_id: &Pubkey) -> Result<Vec<(Pubkey, Account)>> {
+            RpcClient::get_program_accounts(self, program_id).map_err(rpc_error)
+        }
+
+        fn get_slot(&self) -> Result<u64> {
+            RpcClient::get_slot(self).map_err(rpc_error)
+        }
+
+        fn get_latest_blockhash(&self) -> Result<Hash> {
+            RpcClient::get_latest_blockhash(self).map_err(rpc_error)
+        }
+
+        fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+            self.send_and_confirm_transaction(transaction).map_err(rpc_error)
+        }
+
+        fn simulate_transaction(&self, transaction: &Transaction) -> Result<Simulation> {
+            let result = RpcClient::simulate_transaction(self, transaction).map_err(rpc_error)?.value;
+            Ok(Simulation {
+                err: result.err.map(|err| err.to_string()),
+                logs: result.logs.unwrap_or_default(),
+                units_consumed: result.units_consumed,
+            })
+        }
+
+        fn get_transaction_logs(&self, address: &Pubkey, until: Option<&Signature>) -> Result<Vec<TransactionLogs>> {
+            // Signatures come newest first, a page at a time
+            let mut statuses = Vec::new();
+            let mut before = None;
+            loop {
+                let config = GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: until.copied(),
+                    limit: Some(1_000),
+                    commitment: Some(self.commitment()),
+                };
+                let page = self.get_signatures_for_address_with_config(address, config).map_err(rpc_error)?;
+                if page.is_empty() {
+                    break;
+                }
+                for status in page {
+                    let signature = status.signature.parse::<Signature>().map_err(|err| ClientError::Rpc(err.to_string()))?;
+                    before = Some(signature);
+                    // Failed transactions emit no events
+                    if status.err.is_none() {
+                        statuses.push((signature, status.slot, status.block_time));
+                    }
+                }
+            }
+
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.commitment()),
+                max_supported_transaction_version: Some(0),
+            };
+            let mut transactions = Vec::new();
+            for (signature, slot, block_time) in statuses.into_iter().rev() {
+                let transaction = self.get_transaction_with_config(&signature, config).map_err(rpc_error)?;
+                let logs = transaction
+                    .transaction
+                    .meta
+                    .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+                    .unwrap_or_default();
+                transactions.push(TransactionLogs { signature, slot, block_time, logs });
+            }
+            Ok(transactions)
+        }
+    }
+}
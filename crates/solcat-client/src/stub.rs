@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use anchor_lang::AccountSerialize;
+use solana_sdk::account::{create_account_with_fields, Account};
+use solana_sdk::clock::Clock;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+
+use crate::error::{ClientError, Result};
+use crate::rpc::{Simulation, SolcatRpc, TransactionLogs};
+
+// An account stub for tests, not a bank: accounts, the clock, simulation results and
+// transaction logs are set directly. Sent transactions are signature checked and
+// recorded but never executed, so no account changes after a send. Tests of the
+// program's own behaviour belong in the program crate.
+#[derive(Debug, Default)]
+pub struct AccountStubRpc {
+    state: Mutex<StubState>,
+}
+
+#[derive(Debug, Default)]
+struct StubState {
+    accounts: BTreeMap<Pubkey, Account>,
+    transactions: Vec<Transaction>,
+    simulation: Simulation,
+    transaction_logs: Vec<TransactionLogs>,
+    slot: u64,
+}
+
+impl AccountStubRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_account(&self, address: Pubkey, account: Account) {
+        self.state.lock().unwrap().accounts.insert(address, account);
+    }
+
+    // Stores a program account the way Anchor writes it, discriminator first
+    pub fn set_program_account<T: AccountSerialize>(&self, address: Pubkey, value: &T) {
+        let mut data = Vec::new();
+        value.try_serialize(&mut data).expect("account serializes");
+        self.set_account(
+            address,
+            Account {
+                lamports: 1,
+                data,
+                owner: solcat_program::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    pub fn remove_account(&self, address: &Pubkey) -> Option<Account> {
+        self.state.lock().unwrap().accounts.remove(address)
+    }
+
+    // The result every later simulation returns
+    pub fn set_simulation(&self, simulation: Simulation) {
+        self.state.lock().unwrap().simulation = simulation;
+    }
+
+    pub fn set_slot(&self, slot: u64) {
+        self.state.lock().unwrap().slot = slot;
+    }
+
+    // Publishes a clock sysvar at the given time and the current slot
+    pub fn set_clock(&self, unix_timestamp: i64) {
+        let clock = Clock { slot: self.state.lock().unwrap().slot, unix_timestamp, ..Clock::default() };
+        self.set_account(sysvar::clock::ID, create_account_with_fields(&clock, (1, 0)));
+    }
+
+    // Records the logs of a transaction as if the program had run it, at the current slot
+    pub fn push_transaction_logs(&self, signature: Signature, block_time: i64, logs: Vec<String>) {
+        let mut state = self.state.lock().unwrap();
+        let slot = state.slot;
+        state.transaction_logs.push(TransactionLogs { signature, slot, block_time: Some(block_time), logs });
+    }
+
+    pub fn transactions(&self) -> Vec<Transaction> {
+        self.state.lock().unwrap().transactions.clone()
+    }
+}
+
+impl SolcatRpc for AccountStubRpc {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.state.lock().unwrap().accounts.get(address).cloned())
+    }
+
+    fn get_program_accounts(&self, program_id: &Pubkey) -> Result<Vec<(Pubkey, Account)>> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .accounts
+            .iter()
+            .filter(|(_, account)| account.owner == *program_id)
+            .map(|(address, account)| (*address, account.clone()))
+            .collect())
+    }
+
+    fn get_slot(&self) -> Result<u64> {
+        Ok(self.state.lock().unwrap().slot)
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash> {
+        Ok(Hash::default())
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        transaction.verify().map_err(|err| ClientError::Rpc(err.to_string()))?;
+
+        let mut state = self.state.lock().unwrap();
+        state.transactions.push(transaction.clone());
+        state.slot += 1;
+        Ok(transaction.signatures[0])
+    }
+
+    fn simulate_transaction(&self, transaction: &Transaction) -> Result<Simulation> {
+        transaction.verify().map_err(|err| ClientError::Rpc(err.to_string()))?;
+        Ok(self.state.lock().unwrap().simulation.clone())
+    }
+
+    fn get_transaction_logs(&self, _address: &Pubkey, until: Option<&Signature>) -> Result<Vec<TransactionLogs>> {
+        let state = self.state.lock().unwrap();
+        let start = until
+            .and_then(|until| state.transaction_logs.iter().position(|logs| logs.signature == *until))
+            .map_or(0, |position| position + 1);
+        Ok(state.transaction_logs[start..].to_vec())
+    }
+}
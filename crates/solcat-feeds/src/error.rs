@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use solana_sdk::pubkey::Pubkey;
+use solcat_client::ClientError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FeedError {
+    #[error("Failed to read {path}: {source}")]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Feed is not valid CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Feed is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unknown feed format {0:?}, expected csv or json")]
+    UnknownFormat(String),
+    #[error("Progress file {0} belongs to reporter {1}")]
+    ProgressReporterMismatch(PathBuf, String),
+    #[error("Reporter {0} is not registered")]
+    ReporterNotRegistered(Pubkey),
+    #[error("Reporter {0} lacks the reputation to submit reports")]
+    InsufficientReputation(Pubkey),
+    #[error(transparent)]
+    Client(#[from] ClientError),
+}
+
+pub type Result<T> = std::result::Result<T, FeedError>;
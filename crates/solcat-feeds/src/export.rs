@@ -0,0 +1,535 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, SecondsFormat};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use uuid::Uuid;
+
+use solcat_client::events::parse_logs;
+use solcat_client::names::{listing_status_name, risk_type_name};
+use solcat_client::solcat_program::events::SolcatEvent;
+use solcat_client::solcat_program::state::{AddressReport, ListingStatus, ReportHistory, RiskType};
+use solcat_client::{solcat_program, SolcatClient, SolcatRpc};
+
+use crate::error::Result;
+
+// Namespace of the deterministic ids of exported objects, so re-exports update
+// the same STIX objects and MISP attributes instead of duplicating them
+const SOLCAT_NAMESPACE: Uuid = Uuid::from_u128(0x5c0c_a7e1_9b7e_4c3a_a0d6_71f2_3e8b_4d15);
+
+// Reports and listings changed since a point in time
+#[derive(Debug, Default)]
+pub struct ThreatData {
+    pub reports: Vec<(Pubkey, AddressReport)>,
+    pub histories: Vec<(Pubkey, ReportHistory)>,
+    pub removed: Vec<RemovedReport>,
+}
+
+// A quick flag unmarked by its reporter. Its account is closed, so it is
+// only known from the events logged when it was reported and removed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovedReport {
+    pub report: Pubkey,
+    pub reporter: Pubkey,
+    pub reported_address: Pubkey,
+    pub reported_at: i64,
+    pub removed_at: i64,
+}
+
+impl ThreatData {
+    // Everything reported, listed or lifted at or after `since`, 0 for a full export,
+    // including listings that lapsed at their expiry between `since` and `now`
+    pub fn fetch<R: SolcatRpc>(client: &SolcatClient<R>, since: i64, now: i64) -> Result<Self> {
+        let reports = client.fetch_all::<AddressReport>()?;
+        let mut removed = removed_reports(client, since)?;
+        // An address flagged again by the same reporter reuses the removed flag's account
+        removed.retain(|removal| {
+            !reports.iter().any(|(key, report)| *key == removal.report && report.timestamp >= removal.removed_at)
+        });
+        let mut reports: Vec<(Pubkey, AddressReport)> = reports
+            .into_iter()
+            .filter(|(_, report)| report.last_update_time.max(report.timestamp) >= since)
+            .collect();
+        let mut histories: Vec<(Pubkey, ReportHistory)> = client
+            .fetch_all::<ReportHistory>()?
+            .into_iter()
+            .filter(|(_, history)| {
+                let expired = history.blacklist_expiry != 0 && (since..=now).contains(&history.blacklist_expiry);
+                expired || history.blacklist_log.last().is_some_and(|record| record.timestamp >= since)
+            })
+            .collect();
+
+        reports.sort_by_key(|(key, report)| (report.timestamp, *key));
+        histories.sort_by_key(|(key, history)| (history.blacklist_timestamp, *key));
+        Ok(Self { reports, histories, removed })
+    }
+}
+
+// Quick flags removed at or after `since`, from the program's transaction logs
+fn removed_reports<R: SolcatRpc>(client: &SolcatClient<R>, since: i64) -> Result<Vec<RemovedReport>> {
+    let mut reported_at = HashMap::new();
+    let mut removed = Vec::new();
+    for transaction in client.rpc().get_transaction_logs(&solcat_program::ID, None)? {
+        for event in parse_logs(&transaction.logs) {
+            match event {
+                SolcatEvent::AddressReported(reported) => {
+                    reported_at.insert(reported.report, reported.timestamp);
+                }
+                SolcatEvent::ReportRemoved(removal) if removal.timestamp >= since => removed.push(RemovedReport {
+                    report: removal.report,
+                    reporter: removal.reporter,
+                    reported_address: removal.reported_address,
+                    reported_at: reported_at.get(&removal.report).copied().unwrap_or(removal.timestamp),
+                    removed_at: removal.timestamp,
+                }),
+                _ => {}
+            }
+        }
+    }
+    Ok(removed)
+}
+
+fn object_id(kind: &str, key: &Pubkey) -> String {
+    format!("{}--{}", kind, Uuid::new_v5(&SOLCAT_NAMESPACE, key.as_ref()))
+}
+
+fn timestamp(unix_timestamp: i64) -> String {
+    DateTime::from_timestamp(unix_timestamp, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn risk_type_labels(risk_types: &[RiskType]) -> Vec<&'static str> {
+    let mut labels: Vec<&'static str> = risk_types.iter().map(risk_type_name).collect();
+    labels.sort_unstable();
+    labels.dedup();
+    labels
+}
+
+fn address_pattern(address: &Pubkey) -> String {
+    format!("[x-solana-address:value = '{}']", address)
+}
+
+// When the listing was last changed, its lapse counts as a change once the expiry passed
+fn last_listing_change(history: &ReportHistory, now: i64) -> i64 {
+    let logged = history.blacklist_log.last().map_or(history.blacklist_timestamp, |record| record.timestamp);
+    if history.blacklist_expiry != 0 && history.blacklist_expiry <= now {
+        logged.max(history.blacklist_expiry)
+    } else {
+        logged
+    }
+}
+
+// A STIX 2.1 bundle with an indicator per report and per listing, created by a SOLCAT identity
+pub fn stix_bundle(data: &ThreatData, now: i64) -> Value {
+    let identity_id = format!("identity--{}", Uuid::new_v5(&SOLCAT_NAMESPACE, b"identity"));
+    let mut objects = vec![json!({
+        "type": "identity",
+        "spec_version": "2.1",
+        "id": identity_id,
+        "created": timestamp(0),
+        "modified": timestamp(0),
+        "name": "SOLCAT",
+        "identity_class": "system",
+    })];
+
+    for (key, report) in &data.reports {
+        let assessment = &report.risk_assessment;
+        objects.push(json!({
+            "type": "indicator",
+            "spec_version": "2.1",
+            "id": object_id("indicator", key),
+            "created_by_ref": identity_id,
+            "created": timestamp(report.timestamp),
+            "modified": timestamp(report.last_update_time.max(report.timestamp)),
+            "name": format!("Reported Solana address {}", report.reported_address),
+            "description": report.description,
+            "indicator_types": ["malicious-activity"],
+            "labels": risk_type_labels(&assessment.risk_types),
+            "confidence": assessment.confidence_score.min(100),
+            "pattern": address_pattern(&report.reported_address),
+            "pattern_type": "stix",
+            "valid_from": timestamp(report.timestamp),
+            "x_solcat_report": key.to_string(),
+            "x_solcat_reporter": report.reporter.to_string(),
+            "x_solcat_risk_score": report.risk_score,
+            "x_solcat_stake_amount": report.stake_amount,
+            "x_solcat_verified": report.verified,
+        }));
+    }
+
+    // Removed flags revoke the indicator their report was exported as
+    for removed in &data.removed {
+        objects.push(json!({
+            "type": "indicator",
+            "spec_version": "2.1",
+            "id": object_id("indicator", &removed.report),
+            "created_by_ref": identity_id,
+            "created": timestamp(removed.reported_at),
+            "modified": timestamp(removed.removed_at),
+            "name": format!("Reported Solana address {}", removed.reported_address),
+            "description": "Quick flag withdrawn by its reporter",
+            "indicator_types": ["malicious-activity"],
+            "pattern": address_pattern(&removed.reported_address),
+            "pattern_type": "stix",
+            "valid_from": timestamp(removed.reported_at),
+            "revoked": true,
+            "x_solcat_report": removed.report.to_string(),
+            "x_solcat_reporter": removed.reporter.to_string(),
+        }));
+    }
+
+    // Lifted and lapsed listings are exported as revoked so consumers drop them
+    for (key, history) in &data.histories {
+        let status = history.effective_status(now);
+        let last_change = last_listing_change(history, now);
+        let first_listed = history.blacklist_log.first().map_or(history.blacklist_timestamp, |record| record.timestamp);
+        // A lifted listing no longer has a listing time of its own
+        let valid_from = if history.blacklist_timestamp != 0 { history.blacklist_timestamp } else { first_listed };
+        let mut indicator = json!({
+            "type": "indicator",
+            "spec_version": "2.1",
+            "id": object_id("indicator", key),
+            "created_by_ref": identity_id,
+            "created": timestamp(first_listed),
+            "modified": timestamp(last_change),
+            "name": format!("Listed Solana address {}", history.address),
+            "description": history.blacklist_reason,
+            "indicator_types": ["malicious-activity"],
+            "labels": [format!("solcat-listing:{}", listing_status_name(&history.listing_status))],
+            "pattern": address_pattern(&history.address),
+            "pattern_type": "stix",
+            "valid_from": timestamp(valid_from),
+            "revoked": status == ListingStatus::None,
+            "x_solcat_listing_status": listing_status_name(&status),
+        });
+        if history.blacklist_expiry != 0 {
+            indicator["valid_until"] = json!(timestamp(history.blacklist_expiry));
+        }
+        objects.push(indicator);
+    }
+
+    json!({
+        "type": "bundle",
+        "id": format!("bundle--{}", Uuid::new_v5(&SOLCAT_NAMESPACE, format!("bundle:{}", now).as_bytes())),
+        "objects": objects,
+    })
+}
+
+// MISP threat level: 1 high, 2 medium, 3 low, 4 undefined
+fn misp_threat_level(score: u8) -> u8 {
+    match score {
+        0 => 4,
+        1..=39 => 3,
+        40..=69 => 2,
+        _ => 1,
+    }
+}
+
+// A MISP event with an attribute per report and per listing,
+// lifted and lapsed listings are kept with `to_ids` off so consumers stop alerting on them
+pub fn misp_event(data: &ThreatData, now: i64) -> Value {
+    let mut attributes = Vec::new();
+    let mut max_score = 0;
+
+    for (key, report) in &data.reports {
+        max_score = max_score.max(report.risk_score);
+        let mut tags: Vec<Value> = risk_type_labels(&report.risk_assessment.risk_types)
+            .into_iter()
+            .map(|label| json!({ "name": format!("solcat:risk-type=\"{}\"", label) }))
+            .collect();
+        tags.push(json!({ "name": format!("solcat:confidence=\"{}\"", report.risk_assessment.confidence_score) }));
+        attributes.push(json!({
+            "uuid": Uuid::new_v5(&SOLCAT_NAMESPACE, key.as_ref()).to_string(),
+            "type": "text",
+            "category": "Financial fraud",
+            "value": report.reported_address.to_string(),
+            "comment": report.description,
+            "to_ids": true,
+            "timestamp": report.last_update_time.max(report.timestamp).to_string(),
+            "Tag": tags,
+        }));
+    }
+
+    // Removed flags delete the attribute their report was exported as
+    for removed in &data.removed {
+        attributes.push(json!({
+            "uuid": Uuid::new_v5(&SOLCAT_NAMESPACE, removed.report.as_ref()).to_string(),
+            "type": "text",
+            "category": "Financial fraud",
+            "value": removed.reported_address.to_string(),
+            "comment": "Quick flag withdrawn by its reporter",
+            "to_ids": false,
+            "deleted": true,
+            "timestamp": removed.removed_at.to_string(),
+            "Tag": [],
+        }));
+    }
+
+    for (key, history) in &data.histories {
+        let status = history.effective_status(now);
+        if status >= ListingStatus::Block {
+            max_score = 100;
+        }
+        // A removal's rationale is only kept in the log
+        let comment = match history.blacklist_log.last() {
+            Some(record) if history.listing_status == ListingStatus::None => &record.reason,
+            _ => &history.blacklist_reason,
+        };
+        attributes.push(json!({
+            "uuid": Uuid::new_v5(&SOLCAT_NAMESPACE, key.as_ref()).to_string(),
+            "type": "text",
+            "category": "Financial fraud",
+            "value": history.address.to_string(),
+            "comment": comment,
+            "to_ids": status >= ListingStatus::Block,
+            "timestamp": last_listing_change(history, now).to_string(),
+            "Tag": [{ "name": format!("solcat:listing=\"{}\"", listing_status_name(&status)) }],
+        }));
+    }
+
+    let date = DateTime::from_timestamp(now, 0).unwrap_or_default().format("%Y-%m-%d").to_string();
+    json!({
+        "Event": {
+            "uuid": Uuid::new_v5(&SOLCAT_NAMESPACE, format!("misp:{}", now).as_bytes()).to_string(),
+            "info": "SOLCAT Solana address threat intelligence",
+            "date": date,
+            "timestamp": now.to_string(),
+            "threat_level_id": misp_threat_level(max_score).to_string(),
+            "analysis": "2",
+            "distribution": "3",
+            "published": false,
+            "Orgc": { "name": "SOLCAT" },
+            "Tag": [{ "name": "solcat" }],
+            "Attribute": attributes,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::Event;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use solana_sdk::signature::Signature;
+    use solcat_client::solcat_program::events::{AddressReported, ReportRemoved};
+    use solcat_client::solcat_program::state::{BlacklistAction, BlacklistRecord, RiskAssessment, RiskMetrics};
+    use solcat_client::AccountStubRpc;
+
+    use super::*;
+
+    fn report(reported_address: Pubkey, timestamp: i64) -> AddressReport {
+        AddressReport {
+            reporter: Pubkey::new_unique(),
+            reported_address,
+            risk_score: 85,
+            stake_amount: 1_000,
+            timestamp,
+            description: "Wallet drainer".to_string(),
+            vote_weight: 100,
+            last_update_time: timestamp,
+            time_lock_end: 0,
+            risk_assessment: RiskAssessment {
+                base_score: 85,
+                risk_types: vec![RiskType::Phishing, RiskType::Scam],
+                confidence_score: 70,
+                evidence_count: 2,
+                last_update: timestamp,
+            },
+            risk_metrics: RiskMetrics {
+                transaction_volume: 0,
+                unique_interactions: 0,
+                age_of_account: 0,
+                suspicious_patterns: vec![],
+            },
+            verified: false,
+        }
+    }
+
+    fn listed(address: Pubkey, status: ListingStatus, timestamp: i64) -> ReportHistory {
+        let mut history = ReportHistory::new(address);
+        history.listing_status = status;
+        history.blacklist_reason = "Sanctioned mixer".to_string();
+        history.blacklist_timestamp = timestamp;
+        history.blacklist_log.push(BlacklistRecord {
+            action: BlacklistAction::Blacklist,
+            status,
+            authority: Pubkey::new_unique(),
+            reason: history.blacklist_reason.clone(),
+            timestamp,
+            expiry: 0,
+        });
+        history
+    }
+
+    fn data(since: i64) -> (ThreatData, Pubkey) {
+        let rpc = AccountStubRpc::new();
+        let old = Pubkey::new_unique();
+        let new = Pubkey::new_unique();
+        let report_key = Pubkey::new_unique();
+        rpc.set_program_account(Pubkey::new_unique(), &report(old, 100));
+        rpc.set_program_account(report_key, &report(new, 500));
+        rpc.set_program_account(Pubkey::new_unique(), &listed(new, ListingStatus::Block, 600));
+
+        (ThreatData::fetch(&SolcatClient::new(&rpc), since, 1_000).unwrap(), report_key)
+    }
+
+    fn lifted(address: Pubkey, timestamp: i64) -> ReportHistory {
+        let mut history = listed(address, ListingStatus::Block, timestamp);
+        history.listing_status = ListingStatus::None;
+        history.blacklist_reason = String::new();
+        history.blacklist_timestamp = 0;
+        history.blacklist_log.push(BlacklistRecord {
+            action: BlacklistAction::Unblacklist,
+            status: ListingStatus::None,
+            authority: Pubkey::new_unique(),
+            reason: "Appeal upheld".to_string(),
+            timestamp: timestamp + 100,
+            expiry: 0,
+        });
+        history
+    }
+
+    #[test]
+    fn exports_changes_since_a_timestamp_as_stix() {
+        let (data, report_key) = data(200);
+        assert_eq!(data.reports.len(), 1);
+        assert_eq!(data.histories.len(), 1);
+
+        let bundle = stix_bundle(&data, 1_000);
+        let objects = bundle["objects"].as_array().unwrap();
+        assert_eq!(objects.len(), 3);
+
+        let indicator = &objects[1];
+        assert_eq!(indicator["id"], object_id("indicator", &report_key));
+        assert_eq!(indicator["labels"], json!(["phishing", "scam"]));
+        assert_eq!(indicator["confidence"], 70);
+        assert_eq!(indicator["created"], "1970-01-01T00:08:20.000Z");
+        assert_eq!(objects[2]["revoked"], false);
+        assert_eq!(objects[2]["labels"], json!(["solcat-listing:block"]));
+
+        // Ids are stable across exports
+        assert_eq!(stix_bundle(&data, 2_000)["objects"][1]["id"], indicator["id"]);
+    }
+
+    #[test]
+    fn exports_reports_and_active_listings_as_misp() {
+        let (data, _) = data(0);
+
+        let event = misp_event(&data, 1_000);
+        let attributes = event["Event"]["Attribute"].as_array().unwrap();
+        assert_eq!(attributes.len(), 3);
+        assert_eq!(event["Event"]["threat_level_id"], "1");
+        assert_eq!(attributes[0]["Tag"][0]["name"], "solcat:risk-type=\"phishing\"");
+        assert_eq!(attributes[2]["to_ids"], true);
+    }
+
+    #[test]
+    fn exports_lifted_and_lapsed_listings_as_revoked() {
+        let rpc = AccountStubRpc::new();
+        let mut lapsed = listed(Pubkey::new_unique(), ListingStatus::Warn, 100);
+        lapsed.blacklist_expiry = 400;
+        rpc.set_program_account(Pubkey::new_unique(), &lapsed);
+        rpc.set_program_account(Pubkey::new_unique(), &lifted(Pubkey::new_unique(), 150));
+        let data = ThreatData::fetch(&SolcatClient::new(&rpc), 200, 1_000).unwrap();
+        assert_eq!(data.histories.len(), 2);
+
+        let bundle = stix_bundle(&data, 1_000);
+        for indicator in &bundle["objects"].as_array().unwrap()[1..] {
+            assert_eq!(indicator["revoked"], true);
+        }
+        let mut valid_from: Vec<_> = bundle["objects"].as_array().unwrap()[1..]
+            .iter()
+            .map(|indicator| indicator["valid_from"].as_str().unwrap())
+            .collect();
+        valid_from.sort();
+        assert_eq!(valid_from, ["1970-01-01T00:01:40.000Z", "1970-01-01T00:02:30.000Z"]);
+
+        let event = misp_event(&data, 1_000);
+        let attributes = event["Event"]["Attribute"].as_array().unwrap();
+        assert_eq!(attributes.len(), 2);
+        assert!(attributes.iter().all(|attribute| attribute["to_ids"] == false));
+        assert!(attributes.iter().all(|attribute| attribute["Tag"][0]["name"] == "solcat:listing=\"none\""));
+        assert!(attributes.iter().any(|attribute| attribute["timestamp"] == "400"));
+        assert!(attributes.iter().any(|attribute| attribute["comment"] == "Appeal upheld"));
+    }
+
+    fn program_logs(event: &impl Event) -> Vec<String> {
+        vec![
+            format!("Program {} invoke [1]", solcat_program::ID),
+            format!("Program data: {}", STANDARD.encode(event.data())),
+            format!("Program {} success", solcat_program::ID),
+        ]
+    }
+
+    fn flag(rpc: &AccountStubRpc, report: Pubkey, reported_address: Pubkey, reported_at: i64, removed_at: i64) {
+        let reporter = Pubkey::new_unique();
+        let reported = AddressReported {
+            report,
+            reporter,
+            reported_address,
+            risk_score: 50,
+            base_score: 50,
+            confidence_score: 25,
+            vote_weight: 0,
+            stake_amount: 10_000_000,
+            quick_flag: true,
+            timestamp: reported_at,
+        };
+        rpc.push_transaction_logs(Signature::new_unique(), reported_at, program_logs(&reported));
+        let removed = ReportRemoved { report, reporter, reported_address, stake_amount: 10_000_000, timestamp: removed_at };
+        rpc.push_transaction_logs(Signature::new_unique(), removed_at, program_logs(&removed));
+    }
+
+    #[test]
+    fn exports_removed_flags_as_revocations() {
+        let rpc = AccountStubRpc::new();
+        let (withdrawn, address) = (Pubkey::new_unique(), Pubkey::new_unique());
+        flag(&rpc, withdrawn, address, 300, 700);
+        // Flagged again after its removal, the live report is exported instead
+        let reflagged = Pubkey::new_unique();
+        flag(&rpc, reflagged, address, 400, 600);
+        rpc.set_program_account(reflagged, &report(address, 800));
+        // Removed before the export window
+        flag(&rpc, Pubkey::new_unique(), address, 50, 100);
+
+        let data = ThreatData::fetch(&SolcatClient::new(&rpc), 200, 1_000).unwrap();
+        assert_eq!(
+            data.removed,
+            vec![RemovedReport {
+                report: withdrawn,
+                reporter: data.removed[0].reporter,
+                reported_address: address,
+                reported_at: 300,
+                removed_at: 700,
+            }]
+        );
+        assert_eq!(data.reports.len(), 1);
+
+        let bundle = stix_bundle(&data, 1_000);
+        let objects = bundle["objects"].as_array().unwrap();
+        let revoked = objects.iter().find(|object| object["id"] == object_id("indicator", &withdrawn)).unwrap();
+        assert_eq!(revoked["revoked"], true);
+        assert_eq!(revoked["created"], "1970-01-01T00:05:00.000Z");
+        assert_eq!(revoked["modified"], "1970-01-01T00:11:40.000Z");
+        let live = objects.iter().find(|object| object["id"] == object_id("indicator", &reflagged)).unwrap();
+        assert!(live.get("revoked").is_none());
+
+        let event = misp_event(&data, 1_000);
+        let attributes = event["Event"]["Attribute"].as_array().unwrap();
+        assert_eq!(attributes.len(), 2);
+        let deleted = attributes
+            .iter()
+            .find(|attribute| attribute["uuid"] == Uuid::new_v5(&SOLCAT_NAMESPACE, withdrawn.as_ref()).to_string())
+            .unwrap();
+        assert_eq!(deleted["deleted"], true);
+        assert_eq!(deleted["to_ids"], false);
+        assert_eq!(deleted["timestamp"], "700");
+    }
+
+    #[test]
+    fn labels_are_unique_whatever_the_order() {
+        let labels = risk_type_labels(&[RiskType::Scam, RiskType::Phishing, RiskType::Scam]);
+        assert_eq!(labels, ["phishing", "scam"]);
+    }
+}
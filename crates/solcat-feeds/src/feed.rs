@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+use solcat_client::names::parse_risk_type;
+use solcat_client::solcat_program::state::{RiskType, MAX_DESCRIPTION_LEN};
+
+use crate::error::{FeedError, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedFormat {
+    Csv,
+    Json,
+}
+
+impl FeedFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Ok(FeedFormat::Csv),
+            "json" => Ok(FeedFormat::Json),
+            other => Err(FeedError::UnknownFormat(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    pub address: Pubkey,
+    pub risk_type: RiskType,
+    pub score: u8,
+    pub notes: String,
+}
+
+// An entry that failed validation, by its 1-based record number in the feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejected {
+    pub record: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedFeed {
+    pub entries: Vec<FeedEntry>,
+    pub rejected: Vec<Rejected>,
+    // Entries dropped because their address appeared earlier in the feed
+    pub duplicates: usize,
+}
+
+// Fields as they appear in either format, before validation
+#[derive(Debug, Default, Deserialize)]
+struct RawEntry {
+    address: String,
+    risk_type: String,
+    score: String,
+    #[serde(default)]
+    notes: String,
+}
+
+// Feeds hold `address`, `risk_type`, `score` and optional `notes` per entry: a CSV
+// file with those headers or a JSON array of objects with those keys.
+// Notes become the report description, so they must fit one.
+// A repeated address keeps its highest scoring entry.
+pub fn parse_feed(format: FeedFormat, reader: impl Read) -> Result<ParsedFeed> {
+    let raw = match format {
+        FeedFormat::Csv => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader)
+            .deserialize()
+            .map(|record| record.map_err(|err| err.to_string()))
+            .collect::<Vec<_>>(),
+        FeedFormat::Json => {
+            let items: Vec<Value> = serde_json::from_reader(reader)?;
+            items.iter().map(raw_from_json).collect()
+        }
+    };
+
+    let mut feed = ParsedFeed::default();
+    let mut positions: HashMap<Pubkey, usize> = HashMap::new();
+
+    for (index, raw) in raw.into_iter().enumerate() {
+        let entry = match raw.and_then(validate) {
+            Ok(entry) => entry,
+            Err(reason) => {
+                feed.rejected.push(Rejected { record: index + 1, reason });
+                continue;
+            }
+        };
+
+        match positions.get(&entry.address) {
+            Some(&position) => {
+                feed.duplicates += 1;
+                if entry.score > feed.entries[position].score {
+                    feed.entries[position] = entry;
+                }
+            }
+            None => {
+                positions.insert(entry.address, feed.entries.len());
+                feed.entries.push(entry);
+            }
+        }
+    }
+
+    Ok(feed)
+}
+
+fn raw_from_json(item: &Value) -> std::result::Result<RawEntry, String> {
+    let object = item.as_object().ok_or("entry is not an object")?;
+    let field = |name: &str| match object.get(name) {
+        Some(Value::String(s)) => s.trim().to_string(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    };
+    Ok(RawEntry {
+        address: field("address"),
+        risk_type: field("risk_type"),
+        score: field("score"),
+        notes: field("notes"),
+    })
+}
+
+fn validate(raw: RawEntry) -> std::result::Result<FeedEntry, String> {
+    let address = raw
+        .address
+        .parse::<Pubkey>()
+        .map_err(|_| format!("invalid address {:?}", raw.address))?;
+    let risk_type = parse_risk_type(&raw.risk_type).ok_or_else(|| format!("unknown risk type {:?}", raw.risk_type))?;
+    let score = match raw.score.parse::<i64>() {
+        Ok(score @ 0..=100) => score as u8,
+        _ => return Err(format!("score {:?} is not between 0 and 100", raw.score)),
+    };
+    if raw.notes.len() > MAX_DESCRIPTION_LEN {
+        return Err(format!("notes are longer than {} bytes", MAX_DESCRIPTION_LEN));
+    }
+
+    Ok(FeedEntry { address, risk_type, score, notes: raw.notes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_and_deduplicates_csv_entries() {
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let csv = format!(
+            "address,risk_type,score,notes\n\
+             {first},phishing,60,fake mint site\n\
+             {second},Money Laundering,90,\n\
+             {first},scam,80,reported twice\n\
+             not-an-address,scam,50,\n\
+             {second},rugpull,50,\n\
+             {second},scam,101,\n\
+             {second},scam,40,{long}\n",
+            long = "x".repeat(MAX_DESCRIPTION_LEN + 1)
+        );
+
+        let feed = parse_feed(FeedFormat::Csv, csv.as_bytes()).unwrap();
+
+        assert_eq!(feed.entries.len(), 2);
+        assert_eq!(feed.entries[0].address, first);
+        assert_eq!(feed.entries[0].score, 80);
+        assert_eq!(feed.entries[0].risk_type, RiskType::Scam);
+        assert_eq!(feed.entries[1].risk_type, RiskType::MoneyLaundering);
+        assert_eq!(feed.duplicates, 1);
+        assert_eq!(feed.rejected.iter().map(|r| r.record).collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn reads_json_feeds_with_numeric_scores() {
+        let address = Pubkey::new_unique();
+        let json = format!(
+            r#"[{{"address": "{address}", "risk_type": "malware", "score": 75}}, {{"address": "{address}"}}]"#
+        );
+
+        let feed = parse_feed(FeedFormat::Json, json.as_bytes()).unwrap();
+
+        assert_eq!(feed.entries, vec![FeedEntry { address, risk_type: RiskType::Malware, score: 75, notes: String::new() }]);
+        assert_eq!(feed.rejected.len(), 1);
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use solcat_client::solcat_program::instruction as args;
+use solcat_client::solcat_program::state::{ReporterStats, RiskMetrics, MAX_BATCH_SIZE};
+use solcat_client::solcat_program::{
+    MAX_REPORTS_PER_WINDOW, MIN_REPUTATION_SCORE, REPORT_COOLDOWN_PERIOD, REPORT_WINDOW_DURATION,
+};
+use solcat_client::{decode_account, instructions, pda, SolcatClient, SolcatRpc};
+
+use crate::error::{FeedError, Result};
+use crate::feed::FeedEntry;
+
+// Addresses looked up per get_multiple_accounts call
+const LOOKUP_CHUNK: usize = 100;
+
+// Confidence of full reports made from feed entries, the CLI's default for a report
+const FEED_REPORT_CONFIDENCE: u8 = 50;
+
+// Pacing of batch submissions, by default the limits the program applies to every report and batch
+#[derive(Debug, Clone, Copy)]
+pub struct ImportLimits {
+    pub cooldown: i64,
+    pub max_per_window: u32,
+    pub window: i64,
+}
+
+impl Default for ImportLimits {
+    fn default() -> Self {
+        Self {
+            cooldown: REPORT_COOLDOWN_PERIOD,
+            max_per_window: MAX_REPORTS_PER_WINDOW,
+            window: REPORT_WINDOW_DURATION,
+        }
+    }
+}
+
+// A submitted batch report, or a single full report when importing full reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmittedBatch {
+    pub batch_report: String,
+    pub signature: String,
+    pub addresses: Vec<String>,
+    pub submitted_at: i64,
+}
+
+// Written after every batch so an interrupted import resumes where it stopped
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    pub reporter: String,
+    pub batches: Vec<SubmittedBatch>,
+}
+
+impl Progress {
+    // A missing file starts a new import
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        match fs::read(path) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(FeedError::Io { path: path.to_path_buf(), source }),
+        }
+    }
+
+    // Written to a temporary file first so a crash never leaves a truncated progress file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let temporary = path.with_extension("tmp");
+        let io_error = |source| FeedError::Io { path: path.to_path_buf(), source };
+        fs::write(&temporary, serde_json::to_vec_pretty(self)?).map_err(io_error)?;
+        fs::rename(&temporary, path).map_err(io_error)
+    }
+
+    fn submitted_addresses(&self) -> HashSet<&str> {
+        self.batches.iter().flat_map(|batch| batch.addresses.iter().map(String::as_str)).collect()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportOutcome {
+    pub submitted_batches: usize,
+    pub submitted_entries: usize,
+    // Entries whose address already has AddressStats on chain
+    pub skipped_known: usize,
+    // Entries a previous run already submitted
+    pub skipped_submitted: usize,
+    pub remaining: usize,
+    // Set when the limits stopped the import, the earliest time it can continue
+    pub resume_at: Option<i64>,
+}
+
+pub struct Importer<'a, R> {
+    client: &'a SolcatClient<R>,
+    reporter: &'a Keypair,
+    limits: ImportLimits,
+    // Also import addresses that already have AddressStats
+    include_known: bool,
+    // Submit every entry as a full report with its risk type and notes,
+    // batch reports only record addresses and scores
+    full_reports: bool,
+}
+
+impl<'a, R: SolcatRpc> Importer<'a, R> {
+    pub fn new(client: &'a SolcatClient<R>, reporter: &'a Keypair) -> Self {
+        Self { client, reporter, limits: ImportLimits::default(), include_known: false, full_reports: false }
+    }
+
+    pub fn with_limits(mut self, limits: ImportLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn include_known(mut self, include_known: bool) -> Self {
+        self.include_known = include_known;
+        self
+    }
+
+    pub fn full_reports(mut self, full_reports: bool) -> Self {
+        self.full_reports = full_reports;
+        self
+    }
+
+    // Entries per submission, each full report counts against the limits like a batch
+    pub fn submission_size(&self) -> usize {
+        if self.full_reports { 1 } else { MAX_BATCH_SIZE }
+    }
+
+    // Entries still to submit, in feed order, with how many were skipped as known or already submitted
+    pub fn pending(&self, entries: &[FeedEntry], progress: &Progress) -> Result<(Vec<FeedEntry>, usize, usize)> {
+        let submitted = progress.submitted_addresses();
+        let (fresh, resubmitted): (Vec<&FeedEntry>, Vec<&FeedEntry>) =
+            entries.iter().partition(|entry| !submitted.contains(entry.address.to_string().as_str()));
+
+        let known = if self.include_known { HashSet::new() } else { self.known_addresses(&fresh)? };
+        let pending: Vec<FeedEntry> = fresh.into_iter().filter(|entry| !known.contains(&entry.address)).cloned().collect();
+
+        Ok((pending, known.len(), resubmitted.len()))
+    }
+
+    // Addresses that already have AddressStats on chain
+    fn known_addresses(&self, entries: &[&FeedEntry]) -> Result<HashSet<Pubkey>> {
+        let mut known = HashSet::new();
+        for chunk in entries.chunks(LOOKUP_CHUNK) {
+            let keys: Vec<Pubkey> = chunk.iter().map(|entry| pda::address_stats(&entry.address)).collect();
+            let accounts = self.client.rpc().get_multiple_accounts(&keys)?;
+            for (entry, account) in chunk.iter().zip(accounts) {
+                if account.is_some_and(|account| account.owner == solcat_client::solcat_program::ID) {
+                    known.insert(entry.address);
+                }
+            }
+        }
+        Ok(known)
+    }
+
+    // Earliest time the next batch may go out, given the reporter's on-chain limits and earlier batches
+    pub fn next_submission(&self, stats: &ReporterStats, progress: &Progress, now: i64) -> i64 {
+        let limits = &self.limits;
+        let mut next = stats.cooldown_end_time.max(now);
+
+        if let Some(last) = progress.batches.iter().map(|batch| batch.submitted_at).max() {
+            next = next.max(last + limits.cooldown);
+        }
+
+        // Batches count against the same window as the reporter's own reports. The stats already
+        // include every batch up to the last report time, later ones may not have landed yet.
+        let unrecorded: Vec<i64> = progress
+            .batches
+            .iter()
+            .map(|batch| batch.submitted_at)
+            .filter(|submitted_at| *submitted_at > stats.last_report_time && now - submitted_at < limits.window)
+            .collect();
+        let reports_in_window = if now - stats.last_report_time < limits.window { stats.reports_in_window } else { 0 };
+
+        // The program only reopens a full window once a whole window passed without reports
+        if reports_in_window + unrecorded.len() as u32 >= limits.max_per_window {
+            let latest = unrecorded.iter().copied().fold(stats.last_report_time, i64::max);
+            next = next.max(latest + limits.window);
+        }
+
+        next
+    }
+
+    // Submit the pending entries in submission_size chunks until done or the limits call for a pause
+    pub fn run(&self, entries: &[FeedEntry], progress_path: &Path) -> Result<ImportOutcome> {
+        let reporter = self.reporter.pubkey();
+        let mut progress = match Progress::load(progress_path)? {
+            Some(progress) if progress.reporter != reporter.to_string() => {
+                return Err(FeedError::ProgressReporterMismatch(progress_path.to_path_buf(), progress.reporter));
+            }
+            Some(progress) => progress,
+            None => Progress { reporter: reporter.to_string(), batches: Vec::new() },
+        };
+
+        let (pending, skipped_known, skipped_submitted) = self.pending(entries, &progress)?;
+        let mut outcome = ImportOutcome { skipped_known, skipped_submitted, remaining: pending.len(), ..Default::default() };
+
+        for chunk in pending.chunks(self.submission_size()) {
+            let stats = self.reporter_stats()?;
+            let now = self.client.unix_timestamp()?;
+            let next = self.next_submission(&stats, &progress, now);
+            if next > now {
+                outcome.resume_at = Some(next);
+                break;
+            }
+
+            let batch = Keypair::new();
+            let instruction = self.instruction(&batch.pubkey(), chunk);
+            let signature = self.client.send(&[instruction], &reporter, &[self.reporter, &batch])?;
+
+            progress.batches.push(SubmittedBatch {
+                batch_report: batch.pubkey().to_string(),
+                signature: signature.to_string(),
+                addresses: chunk.iter().map(|entry| entry.address.to_string()).collect(),
+                submitted_at: now,
+            });
+            progress.save(progress_path)?;
+
+            outcome.submitted_batches += 1;
+            outcome.submitted_entries += chunk.len();
+            outcome.remaining -= chunk.len();
+        }
+
+        Ok(outcome)
+    }
+
+    // The new account is the batch report, or the report for a full report's single entry
+    fn instruction(&self, account: &Pubkey, chunk: &[FeedEntry]) -> Instruction {
+        let reporter = self.reporter.pubkey();
+        match chunk {
+            [entry] if self.full_reports => instructions::report_address(
+                &reporter,
+                &entry.address,
+                account,
+                args::ReportAddress {
+                    risk_score: entry.score,
+                    description: entry.notes.clone(),
+                    risk_types: vec![entry.risk_type.clone()],
+                    confidence_score: FEED_REPORT_CONFIDENCE,
+                    evidence_count: 0,
+                    risk_metrics: RiskMetrics {
+                        transaction_volume: 0,
+                        unique_interactions: 0,
+                        age_of_account: 0,
+                        suspicious_patterns: vec![],
+                    },
+                },
+            ),
+            _ => instructions::submit_batch_report(
+                &reporter,
+                account,
+                chunk.iter().map(|entry| entry.address).collect(),
+                chunk.iter().map(|entry| entry.score).collect(),
+            ),
+        }
+    }
+
+    fn reporter_stats(&self) -> Result<ReporterStats> {
+        let reporter = self.reporter.pubkey();
+        let key = pda::reporter_stats(&reporter);
+        let account = self.client.rpc().get_account(&key)?.ok_or(FeedError::ReporterNotRegistered(reporter))?;
+        let stats: ReporterStats = decode_account(&key, &account)?;
+        if stats.reputation_score < MIN_REPUTATION_SCORE {
+            return Err(FeedError::InsufficientReputation(reporter));
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solcat_client::solcat_program::state::{AddressStats, RiskType};
+    use solcat_client::AccountStubRpc;
+
+    use super::*;
+
+    fn entries(count: usize) -> Vec<FeedEntry> {
+        (0..count)
+            .map(|i| FeedEntry {
+                address: Pubkey::new_unique(),
+                risk_type: RiskType::Scam,
+                score: (i % 100) as u8,
+                notes: String::new(),
+            })
+            .collect()
+    }
+
+    fn reporter_stats() -> ReporterStats {
+        ReporterStats {
+            total_reports: 0,
+            successful_reports: 0,
+            total_stake: 0,
+            quick_flag_stake: 0,
+            reputation_score: MIN_REPUTATION_SCORE,
+            last_report_time: 0,
+            reports_in_window: 0,
+            cooldown_end_time: 0,
+            token_balance: 0,
+            rewards_claimed: 0,
+            reward_points: 0,
+            points_epoch: 0,
+            delegated_stake: 0,
+            delegation_shares: 0,
+            commission_bps: 0,
+            delegation_reward_per_share: 0,
+        }
+    }
+
+    fn register(rpc: &AccountStubRpc, reporter: &Pubkey) {
+        rpc.set_program_account(pda::reporter_stats(reporter), &reporter_stats());
+    }
+
+    fn progress_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("solcat-import-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn imports_in_paced_batches_and_resumes() {
+        let rpc = AccountStubRpc::new();
+        let client = SolcatClient::new(&rpc);
+        let reporter = Keypair::new();
+        register(&rpc, &reporter.pubkey());
+
+        // One feed address is already tracked on chain
+        let feed = entries(25);
+        rpc.set_program_account(pda::address_stats(&feed[3].address), &AddressStats {
+            address: feed[3].address,
+            total_reports: 1,
+            risk_scores: vec![50],
+            risk_score_times: vec![0],
+            total_stake: 0,
+            last_update: 0,
+            last_report_time: 0,
+            weighted_risk_score: 50,
+            total_vote_weight: 1,
+            reporters: vec![],
+            total_confidence: 50,
+        });
+
+        let path = progress_path("paced");
+        let importer = Importer::new(&client, &reporter);
+
+        // The cooldown stops the import after the first batch
+        rpc.set_clock(1_000);
+        let outcome = importer.run(&feed, &path).unwrap();
+        assert_eq!(outcome.submitted_batches, 1);
+        assert_eq!(outcome.submitted_entries, MAX_BATCH_SIZE);
+        assert_eq!(outcome.skipped_known, 1);
+        assert_eq!(outcome.remaining, 24 - MAX_BATCH_SIZE);
+        assert_eq!(outcome.resume_at, Some(1_000 + REPORT_COOLDOWN_PERIOD));
+
+        // Resuming after the cooldown skips what was already submitted
+        rpc.set_clock(1_000 + REPORT_COOLDOWN_PERIOD);
+        let outcome = importer.run(&feed, &path).unwrap();
+        assert_eq!(outcome.skipped_submitted, MAX_BATCH_SIZE);
+        assert_eq!(outcome.submitted_batches, 1);
+        assert_eq!(outcome.remaining, 24 - 2 * MAX_BATCH_SIZE);
+
+        let progress = Progress::load(&path).unwrap().unwrap();
+        assert_eq!(progress.batches.len(), 2);
+        assert_eq!(rpc.transactions().len(), 2);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn full_reports_keep_risk_types_and_notes() {
+        let rpc = AccountStubRpc::new();
+        let client = SolcatClient::new(&rpc);
+        let reporter = Keypair::new();
+        register(&rpc, &reporter.pubkey());
+
+        let mut feed = entries(2);
+        feed[0].risk_type = RiskType::Phishing;
+        feed[0].notes = "fake mint site".to_string();
+
+        let path = progress_path("full");
+        let importer = Importer::new(&client, &reporter).full_reports(true);
+        rpc.set_clock(1_000);
+        let outcome = importer.run(&feed, &path).unwrap();
+        assert_eq!(outcome.submitted_entries, 1);
+        assert_eq!(outcome.remaining, 1);
+
+        // The report account is the third of report_address
+        let message = &rpc.transactions()[0].message;
+        let report = message.account_keys[message.instructions[0].accounts[2] as usize];
+        let expected = instructions::report_address(&reporter.pubkey(), &feed[0].address, &report, args::ReportAddress {
+            risk_score: feed[0].score,
+            description: "fake mint site".to_string(),
+            risk_types: vec![RiskType::Phishing],
+            confidence_score: FEED_REPORT_CONFIDENCE,
+            evidence_count: 0,
+            risk_metrics: RiskMetrics { transaction_volume: 0, unique_interactions: 0, age_of_account: 0, suspicious_patterns: vec![] },
+        });
+        assert_eq!(message.instructions[0].data, expected.data);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn waits_for_the_window_to_reopen() {
+        let rpc = AccountStubRpc::new();
+        let client = SolcatClient::new(&rpc);
+        let reporter = Keypair::new();
+        let importer = Importer::new(&client, &reporter).with_limits(ImportLimits { cooldown: 0, max_per_window: 2, window: 100 });
+        let batch = |submitted_at| SubmittedBatch {
+            batch_report: String::new(),
+            signature: String::new(),
+            addresses: vec![],
+            submitted_at,
+        };
+
+        let mut stats = reporter_stats();
+        let progress = Progress { reporter: reporter.pubkey().to_string(), batches: vec![batch(10), batch(40)] };
+        assert_eq!(importer.next_submission(&stats, &progress, 50), 140);
+        assert_eq!(importer.next_submission(&stats, &progress, 150), 150);
+
+        // Reports the reporter made on chain fill the same window, including the batch they recorded
+        stats.last_report_time = 45;
+        stats.reports_in_window = 1;
+        let progress = Progress { reporter: reporter.pubkey().to_string(), batches: vec![batch(40)] };
+        assert_eq!(importer.next_submission(&stats, &progress, 50), 50);
+        let progress = Progress { reporter: reporter.pubkey().to_string(), batches: vec![batch(40), batch(48)] };
+        assert_eq!(importer.next_submission(&stats, &progress, 50), 148);
+    }
+}
@@ -0,0 +1,12 @@
+// Threat feed interchange for SOLCAT: importing CSV and JSON feeds as batch reports
+// and exporting reports and listings as STIX 2.1 and MISP
+
+pub mod error;
+pub mod export;
+pub mod feed;
+pub mod import;
+
+pub use error::{FeedError, Result};
+pub use export::{misp_event, stix_bundle, RemovedReport, ThreatData};
+pub use feed::{parse_feed, FeedEntry, FeedFormat, ParsedFeed, Rejected};
+pub use import::{ImportLimits, ImportOutcome, Importer, Progress, SubmittedBatch};
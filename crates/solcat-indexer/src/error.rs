@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use solcat_client::ClientError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("Failed to read {path}: {source}")]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Snapshot file {path} is invalid: {reason}")]
+    InvalidSnapshot { path: PathBuf, reason: String },
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[cfg(feature = "postgres")]
+    #[error("Postgres error: {0}")]
+    Postgres(#[from] postgres::Error),
+    #[error("Value {0} does not fit a BIGINT column")]
+    ValueOutOfRange(u64),
+    #[error("Result column {0} holds a value the index never writes")]
+    UnexpectedValue(usize),
+    #[error("Column {0} has a type the index does not use")]
+    UnsupportedColumn(String),
+    #[error("No database backend for {0:?} was compiled in")]
+    UnsupportedDatabase(String),
+    #[error(transparent)]
+    Client(#[from] ClientError),
+}
+
+pub type Result<T> = std::result::Result<T, IndexerError>;
@@ -0,0 +1,627 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use anchor_lang::Discriminator;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use solcat_client::events::parse_logs;
+use solcat_client::names::{blacklist_action_name, listing_status_name, risk_type_name, verification_status_name};
+use solcat_client::solcat_program::state::{
+    AddressReport, AddressStats, BatchReport, ReportHistory, ReporterStats, UserStake,
+};
+use solcat_client::{decode_account, pda, solcat_program, SolcatRpc, TransactionLogs};
+
+use crate::error::Result;
+use crate::schema::{ACCOUNT_TABLES, CHILD_TABLES, SCHEMA};
+use crate::store::{SqlValue, Store};
+
+// What an ingest did
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IndexStats {
+    // Rows written or rewritten at the same slot
+    pub indexed: usize,
+    // Accounts older than the indexed row
+    pub stale: usize,
+    // Accounts of other programs or of types the index does not keep
+    pub skipped: usize,
+    // Accounts of an indexed type that failed to decode
+    pub invalid: usize,
+    // Rows of accounts closed before the ingested slot
+    pub pruned: u64,
+    pub events: usize,
+}
+
+impl fmt::Display for IndexStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} indexed, {} stale, {} skipped, {} invalid, {} pruned, {} events",
+            self.indexed, self.stale, self.skipped, self.invalid, self.pruned, self.events
+        )
+    }
+}
+
+enum Decoded {
+    Report(AddressReport),
+    Stats(AddressStats),
+    ReporterStats(ReporterStats),
+    Stake(UserStake),
+    Batch(BatchReport),
+    History(ReportHistory),
+}
+
+// None for accounts the index does not keep
+fn decode(address: &Pubkey, account: &Account) -> Option<solcat_client::Result<Decoded>> {
+    if account.owner != solcat_program::ID || account.data.len() < 8 {
+        return None;
+    }
+    let discriminator = &account.data[..8];
+    let decoded = if discriminator == AddressReport::DISCRIMINATOR {
+        decode_account(address, account).map(Decoded::Report)
+    } else if discriminator == AddressStats::DISCRIMINATOR {
+        decode_account(address, account).map(Decoded::Stats)
+    } else if discriminator == ReporterStats::DISCRIMINATOR {
+        decode_account(address, account).map(Decoded::ReporterStats)
+    } else if discriminator == UserStake::DISCRIMINATOR {
+        decode_account(address, account).map(Decoded::Stake)
+    } else if discriminator == BatchReport::DISCRIMINATOR {
+        decode_account(address, account).map(Decoded::Batch)
+    } else if discriminator == ReportHistory::DISCRIMINATOR {
+        decode_account(address, account).map(Decoded::History)
+    } else {
+        return None;
+    };
+    Some(decoded)
+}
+
+fn json_list<T>(items: &[T], name: impl Fn(&T) -> String) -> SqlValue {
+    SqlValue::Text(serde_json::Value::from(items.iter().map(name).collect::<Vec<_>>()).to_string())
+}
+
+const LAST_SIGNATURE: &str = "last_signature";
+
+// Materializes SOLCAT accounts and events into a store. Every ingest runs in one
+// transaction and is idempotent: rows only move forward in slot.
+pub struct Indexer<S> {
+    store: S,
+}
+
+impl<S: Store> Indexer<S> {
+    // Creates the tables that do not exist yet
+    pub fn new(mut store: S) -> Result<Self> {
+        store.execute_batch(SCHEMA)?;
+        Ok(Self { store })
+    }
+
+    pub fn store(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    // Indexes accounts read at `slot`. A complete snapshot also drops the rows of
+    // accounts it lacks, which were closed since they were indexed.
+    pub fn index_snapshot(&mut self, slot: u64, accounts: &[(Pubkey, Account)], complete: bool) -> Result<IndexStats> {
+        self.in_transaction(|indexer| {
+            let mut stats = indexer.index_accounts(slot, accounts)?;
+            if complete {
+                stats.pruned = indexer.prune(slot)?;
+            }
+            Ok(stats)
+        })
+    }
+
+    // Indexes every program account and the events of transactions since the last sync
+    pub fn sync<R: SolcatRpc>(&mut self, rpc: &R) -> Result<IndexStats> {
+        // Read before the accounts, so rows are never labelled with a slot newer than their state
+        let slot = rpc.get_slot()?;
+        let accounts = rpc.get_program_accounts(&solcat_program::ID)?;
+        let until = self.last_signature()?;
+        let transactions = rpc.get_transaction_logs(&solcat_program::ID, until.as_ref())?;
+
+        self.in_transaction(|indexer| {
+            let mut stats = indexer.index_accounts(slot, &accounts)?;
+            stats.pruned = indexer.prune(slot)?;
+            stats.events = indexer.index_transactions(&transactions)?;
+            Ok(stats)
+        })
+    }
+
+    // The newest transaction whose events were indexed
+    pub fn last_signature(&mut self) -> Result<Option<Signature>> {
+        let rows = self
+            .store
+            .query("SELECT value FROM sync_state WHERE key = $1", &[LAST_SIGNATURE.into()])?;
+        Ok(rows
+            .first()
+            .and_then(|row| row[0].as_str())
+            .and_then(|value| value.parse().ok()))
+    }
+
+    fn in_transaction<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.store.execute_batch("BEGIN")?;
+        match f(self) {
+            Ok(value) => {
+                self.store.execute_batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(err) => {
+                let _ = self.store.execute_batch("ROLLBACK");
+                Err(err)
+            }
+        }
+    }
+
+    fn index_accounts(&mut self, slot: u64, accounts: &[(Pubkey, Account)]) -> Result<IndexStats> {
+        let mut stats = IndexStats::default();
+        let mut decoded = Vec::new();
+        for (address, account) in accounts {
+            match decode(address, account) {
+                Some(Ok(value)) => decoded.push((address, value)),
+                Some(Err(_)) => stats.invalid += 1,
+                None => stats.skipped += 1,
+            }
+        }
+
+        // Reporter stats do not hold their reporter, it is recovered from the
+        // reporters seen in the same ingest
+        let mut seen = HashSet::new();
+        for (_, value) in &decoded {
+            match value {
+                Decoded::Report(report) => {
+                    seen.insert(report.reporter);
+                }
+                Decoded::Batch(batch) => {
+                    seen.insert(batch.reporter);
+                }
+                Decoded::History(history) => seen.extend(history.reports.iter().map(|report| report.reporter)),
+                _ => {}
+            }
+        }
+        let reporters: HashMap<Pubkey, Pubkey> =
+            seen.into_iter().map(|reporter| (pda::reporter_stats(&reporter), reporter)).collect();
+
+        for (address, value) in decoded {
+            let written = match value {
+                Decoded::Report(report) => self.upsert_report(address, slot, &report)?,
+                Decoded::Stats(address_stats) => self.upsert_address_stats(address, slot, &address_stats)?,
+                Decoded::ReporterStats(reporter_stats) => {
+                    self.upsert_reporter_stats(address, slot, reporters.get(address), &reporter_stats)?
+                }
+                Decoded::Stake(stake) => self.upsert_stake(address, slot, &stake)?,
+                Decoded::Batch(batch) => self.upsert_batch(address, slot, &batch)?,
+                Decoded::History(history) => self.upsert_history(address, slot, &history)?,
+            };
+            if written {
+                stats.indexed += 1;
+            } else {
+                stats.stale += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    // Inserts or updates the row of an account unless the indexed row was read at a
+    // later slot. Values missing from this ingest keep what was indexed before.
+    fn upsert(&mut self, table: &str, account: &Pubkey, slot: u64, columns: Vec<(&str, SqlValue)>) -> Result<bool> {
+        let names: Vec<&str> = columns.iter().map(|(name, _)| *name).collect();
+        let placeholders: Vec<String> = (3..names.len() + 3).map(|index| format!("${}", index)).collect();
+        let updates: Vec<String> = names
+            .iter()
+            .map(|name| format!("{name} = COALESCE(excluded.{name}, {table}.{name})"))
+            .collect();
+        let sql = format!(
+            "INSERT INTO {table} (account, slot, {}) VALUES ($1, $2, {}) \
+             ON CONFLICT (account) DO UPDATE SET slot = excluded.slot, {} \
+             WHERE excluded.slot >= {table}.slot",
+            names.join(", "),
+            placeholders.join(", "),
+            updates.join(", "),
+        );
+
+        let mut params = vec![SqlValue::from(*account), SqlValue::try_from(slot)?];
+        params.extend(columns.into_iter().map(|(_, value)| value));
+        Ok(self.store.execute(&sql, &params)? > 0)
+    }
+
+    // Replaces the rows an account owns, numbered by their position in the account
+    fn replace_children(&mut self, table: &str, owner_column: &str, owner: &Pubkey, columns: &[&str], rows: Vec<Vec<SqlValue>>) -> Result<()> {
+        self.store
+            .execute(&format!("DELETE FROM {table} WHERE {owner_column} = $1"), &[(*owner).into()])?;
+
+        let placeholders: Vec<String> = (3..columns.len() + 3).map(|index| format!("${}", index)).collect();
+        let sql = format!(
+            "INSERT INTO {table} ({owner_column}, position, {}) VALUES ($1, $2, {})",
+            columns.join(", "),
+            placeholders.join(", "),
+        );
+        for (position, row) in rows.into_iter().enumerate() {
+            let mut params = vec![SqlValue::from(*owner), SqlValue::try_from(position as u64)?];
+            params.extend(row);
+            self.store.execute(&sql, &params)?;
+        }
+        Ok(())
+    }
+
+    fn upsert_report(&mut self, account: &Pubkey, slot: u64, report: &AddressReport) -> Result<bool> {
+        let assessment = &report.risk_assessment;
+        let metrics = &report.risk_metrics;
+        self.upsert(
+            "reports",
+            account,
+            slot,
+            vec![
+                ("reporter", report.reporter.into()),
+                ("reported_address", report.reported_address.into()),
+                ("risk_score", report.risk_score.into()),
+                ("stake_amount", report.stake_amount.try_into()?),
+                ("timestamp", report.timestamp.into()),
+                ("description", report.description.as_str().into()),
+                ("vote_weight", report.vote_weight.try_into()?),
+                ("last_update_time", report.last_update_time.into()),
+                ("time_lock_end", report.time_lock_end.into()),
+                ("base_score", assessment.base_score.into()),
+                ("risk_types", json_list(&assessment.risk_types, |risk_type| risk_type_name(risk_type).to_string())),
+                ("confidence_score", assessment.confidence_score.into()),
+                ("evidence_count", assessment.evidence_count.into()),
+                ("assessment_updated_at", assessment.last_update.into()),
+                ("transaction_volume", metrics.transaction_volume.try_into()?),
+                ("unique_interactions", metrics.unique_interactions.into()),
+                ("age_of_account", metrics.age_of_account.into()),
+                ("suspicious_patterns", json_list(&metrics.suspicious_patterns, String::clone)),
+                ("verified", report.verified.into()),
+            ],
+        )
+    }
+
+    fn upsert_address_stats(&mut self, account: &Pubkey, slot: u64, stats: &AddressStats) -> Result<bool> {
+        self.upsert(
+            "address_stats",
+            account,
+            slot,
+            vec![
+                ("address", stats.address.into()),
+                ("total_reports", stats.total_reports.into()),
+                ("total_stake", stats.total_stake.try_into()?),
+                ("last_update", stats.last_update.into()),
+                ("weighted_risk_score", stats.weighted_risk_score.into()),
+                ("total_vote_weight", stats.total_vote_weight.try_into()?),
+                ("total_confidence", stats.total_confidence.try_into()?),
+                ("distinct_reporters", (stats.reporters.len() as u64).try_into()?),
+                ("aggregate_score", stats.aggregate_score().into()),
+                ("confidence", stats.confidence().into()),
+            ],
+        )
+    }
+
+    fn upsert_reporter_stats(&mut self, account: &Pubkey, slot: u64, reporter: Option<&Pubkey>, stats: &ReporterStats) -> Result<bool> {
+        self.upsert(
+            "reporter_stats",
+            account,
+            slot,
+            vec![
+                ("reporter", reporter.copied().into()),
+                ("total_reports", stats.total_reports.into()),
+                ("successful_reports", stats.successful_reports.into()),
+                ("total_stake", stats.total_stake.try_into()?),
+                ("reputation_score", stats.reputation_score.into()),
+                ("last_report_time", stats.last_report_time.into()),
+                ("reports_in_window", stats.reports_in_window.into()),
+                ("cooldown_end_time", stats.cooldown_end_time.into()),
+                ("token_balance", stats.token_balance.try_into()?),
+                ("rewards_claimed", stats.rewards_claimed.try_into()?),
+                ("reward_points", stats.reward_points.try_into()?),
+                ("points_epoch", stats.points_epoch.try_into()?),
+                ("delegated_stake", stats.delegated_stake.try_into()?),
+                ("delegation_shares", stats.delegation_shares.try_into()?),
+                ("commission_bps", stats.commission_bps.into()),
+                ("delegation_reward_per_share", stats.delegation_reward_per_share.into()),
+            ],
+        )
+    }
+
+    fn upsert_stake(&mut self, account: &Pubkey, slot: u64, stake: &UserStake) -> Result<bool> {
+        self.upsert(
+            "stakes",
+            account,
+            slot,
+            vec![
+                ("owner", stake.owner.into()),
+                ("position", stake.position.into()),
+                ("amount", stake.amount.try_into()?),
+                ("weight", stake.weight.try_into()?),
+                ("rewards_earned", stake.rewards_earned.try_into()?),
+                ("reward_per_token_paid", stake.reward_per_token_paid.into()),
+                ("lock_end_time", stake.lock_end_time.into()),
+            ],
+        )
+    }
+
+    fn upsert_batch(&mut self, account: &Pubkey, slot: u64, batch: &BatchReport) -> Result<bool> {
+        let written = self.upsert(
+            "batches",
+            account,
+            slot,
+            vec![
+                ("reporter", batch.reporter.into()),
+                ("timestamp", batch.timestamp.into()),
+                ("verification_status", verification_status_name(&batch.verification_status).into()),
+            ],
+        )?;
+        if written {
+            let entries = batch
+                .addresses
+                .iter()
+                .zip(&batch.risk_scores)
+                .map(|(address, score)| vec![(*address).into(), (*score).into()])
+                .collect();
+            self.replace_children("batch_entries", "batch", account, &["address", "risk_score"], entries)?;
+        }
+        Ok(written)
+    }
+
+    fn upsert_history(&mut self, account: &Pubkey, slot: u64, history: &ReportHistory) -> Result<bool> {
+        let written = self.upsert(
+            "histories",
+            account,
+            slot,
+            vec![
+                ("address", history.address.into()),
+                ("listing_status", listing_status_name(&history.listing_status).into()),
+                ("blacklist_reason", history.blacklist_reason.as_str().into()),
+                ("blacklist_timestamp", history.blacklist_timestamp.into()),
+                ("blacklist_expiry", history.blacklist_expiry.into()),
+            ],
+        )?;
+        if written {
+            let reports = history
+                .reports
+                .iter()
+                .map(|report| {
+                    vec![
+                        report.timestamp.into(),
+                        report.risk_score.into(),
+                        report.reporter.into(),
+                        report.description.as_str().into(),
+                    ]
+                })
+                .collect();
+            self.replace_children(
+                "history_reports",
+                "history",
+                account,
+                &["timestamp", "risk_score", "reporter", "description"],
+                reports,
+            )?;
+
+            let log = history
+                .blacklist_log
+                .iter()
+                .map(|record| {
+                    vec![
+                        blacklist_action_name(&record.action).into(),
+                        listing_status_name(&record.status).into(),
+                        record.authority.into(),
+                        record.reason.as_str().into(),
+                        record.timestamp.into(),
+                        record.expiry.into(),
+                    ]
+                })
+                .collect();
+            self.replace_children(
+                "blacklist_log",
+                "history",
+                account,
+                &["action", "status", "authority", "reason", "timestamp", "expiry"],
+                log,
+            )?;
+        }
+        Ok(written)
+    }
+
+    fn prune(&mut self, slot: u64) -> Result<u64> {
+        let mut pruned = 0;
+        for table in ACCOUNT_TABLES {
+            pruned += self
+                .store
+                .execute(&format!("DELETE FROM {table} WHERE slot < $1"), &[slot.try_into()?])?;
+        }
+        for (table, owner_column, owner_table) in CHILD_TABLES {
+            self.store.execute(
+                &format!("DELETE FROM {table} WHERE {owner_column} NOT IN (SELECT account FROM {owner_table})"),
+                &[],
+            )?;
+        }
+        Ok(pruned)
+    }
+
+    fn index_transactions(&mut self, transactions: &[TransactionLogs]) -> Result<usize> {
+        let mut indexed = 0;
+        for transaction in transactions {
+            for (position, event) in parse_logs(&transaction.logs).into_iter().enumerate() {
+                indexed += self.store.execute(
+                    "INSERT INTO events (signature, position, slot, block_time, name, data) \
+                     VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (signature, position) DO NOTHING",
+                    &[
+                        transaction.signature.to_string().into(),
+                        (position as u64).try_into()?,
+                        transaction.slot.try_into()?,
+                        transaction.block_time.into(),
+                        event.name().into(),
+                        STANDARD.encode(event.data()).into(),
+                    ],
+                )? as usize;
+            }
+        }
+
+        if let Some(last) = transactions.last() {
+            self.store.execute(
+                "INSERT INTO sync_state (key, value) VALUES ($1, $2) \
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+                &[LAST_SIGNATURE.into(), last.signature.to_string().into()],
+            )?;
+        }
+        Ok(indexed)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use anchor_lang::Event;
+    use rusqlite::Connection;
+    use solcat_client::solcat_program::events::ReportUpdated;
+    use solcat_client::solcat_program::state::{
+        HistoricalReport, ListingStatus, RiskAssessment, RiskMetrics, RiskType, VerificationStatus,
+    };
+    use solcat_client::AccountStubRpc;
+
+    use super::*;
+    use crate::error::IndexerError;
+
+    fn report(reporter: Pubkey, risk_score: u8) -> AddressReport {
+        AddressReport {
+            reporter,
+            reported_address: Pubkey::new_unique(),
+            risk_score,
+            stake_amount: 1_000,
+            timestamp: 100,
+            description: "Wallet drainer".to_string(),
+            vote_weight: 100,
+            last_update_time: 100,
+            time_lock_end: 0,
+            risk_assessment: RiskAssessment {
+                base_score: risk_score,
+                risk_types: vec![RiskType::Phishing],
+                confidence_score: 70,
+                evidence_count: 1,
+                last_update: 100,
+            },
+            risk_metrics: RiskMetrics {
+                transaction_volume: 0,
+                unique_interactions: 0,
+                age_of_account: 0,
+                suspicious_patterns: vec!["approval phishing".to_string()],
+            },
+            verified: false,
+        }
+    }
+
+    fn reporter_stats() -> ReporterStats {
+        ReporterStats {
+            total_reports: 1,
+            successful_reports: 0,
+            total_stake: 1_000,
+            quick_flag_stake: 0,
+            reputation_score: 50,
+            last_report_time: 100,
+            reports_in_window: 1,
+            cooldown_end_time: 0,
+            token_balance: 0,
+            rewards_claimed: 0,
+            reward_points: 0,
+            points_epoch: 0,
+            delegated_stake: 0,
+            delegation_shares: 0,
+            commission_bps: 0,
+            delegation_reward_per_share: 0,
+        }
+    }
+
+    fn count(indexer: &mut Indexer<Connection>, sql: &str) -> i64 {
+        indexer.store().query(sql, &[]).unwrap()[0][0].as_i64().unwrap()
+    }
+
+    #[test]
+    fn upserts_move_forward_in_slot_and_prune_closed_accounts() {
+        let rpc = AccountStubRpc::new();
+        let reporter = Pubkey::new_unique();
+        let (report_key, batch_key, history_key) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        rpc.set_program_account(report_key, &report(reporter, 60));
+        rpc.set_program_account(pda::reporter_stats(&reporter), &reporter_stats());
+        rpc.set_program_account(
+            batch_key,
+            &BatchReport {
+                reporter,
+                addresses: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+                risk_scores: vec![40, 90],
+                timestamp: 100,
+                verification_status: VerificationStatus::Pending,
+            },
+        );
+        let mut history = ReportHistory::new(Pubkey::new_unique());
+        history.listing_status = ListingStatus::Warn;
+        history.reports.push(HistoricalReport { timestamp: 100, risk_score: 60, reporter, description: String::new() });
+        rpc.set_program_account(history_key, &history);
+        rpc.set_slot(10);
+
+        let mut indexer = Indexer::new(Connection::open_in_memory().unwrap()).unwrap();
+        let stats = indexer.sync(&rpc).unwrap();
+        assert_eq!((stats.indexed, stats.skipped, stats.pruned), (4, 0, 0));
+        assert_eq!(count(&mut indexer, "SELECT COUNT(*) FROM batch_entries"), 2);
+        let rows = indexer.store().query("SELECT reporter FROM reporter_stats", &[]).unwrap();
+        assert_eq!(rows[0][0], SqlValue::from(reporter));
+
+        // Re-ingesting the same slot changes nothing, an older snapshot is ignored
+        assert_eq!(indexer.sync(&rpc).unwrap().indexed, 4);
+        assert_eq!(count(&mut indexer, "SELECT COUNT(*) FROM batch_entries"), 2);
+        let older = [(report_key, rpc.get_account(&report_key).unwrap().unwrap())];
+        rpc.set_program_account(report_key, &report(reporter, 95));
+        assert_eq!(indexer.index_snapshot(5, &older, false).unwrap().stale, 1);
+
+        // A later sync applies changes and drops closed accounts with what they own
+        rpc.remove_account(&batch_key);
+        rpc.set_slot(20);
+        let stats = indexer.sync(&rpc).unwrap();
+        assert_eq!((stats.indexed, stats.pruned), (3, 1));
+        assert_eq!(count(&mut indexer, "SELECT risk_score FROM reports"), 95);
+        assert_eq!(count(&mut indexer, "SELECT COUNT(*) FROM batch_entries"), 0);
+        assert_eq!(count(&mut indexer, "SELECT COUNT(*) FROM history_reports"), 1);
+    }
+
+    #[test]
+    fn rejects_amounts_beyond_bigint() {
+        let rpc = AccountStubRpc::new();
+        let mut stats = reporter_stats();
+        stats.total_stake = u64::MAX;
+        rpc.set_program_account(pda::reporter_stats(&Pubkey::new_unique()), &stats);
+
+        let mut indexer = Indexer::new(Connection::open_in_memory().unwrap()).unwrap();
+        assert!(matches!(indexer.sync(&rpc), Err(IndexerError::ValueOutOfRange(u64::MAX))));
+        assert_eq!(count(&mut indexer, "SELECT COUNT(*) FROM reporter_stats"), 0);
+    }
+
+    #[test]
+    fn indexes_events_of_new_transactions_once() {
+        let rpc = AccountStubRpc::new();
+        let event = ReportUpdated {
+            report: Pubkey::new_unique(),
+            reporter: Pubkey::new_unique(),
+            reported_address: Pubkey::new_unique(),
+            risk_score: 80,
+            timestamp: 100,
+        };
+        let logs = vec![
+            format!("Program {} invoke [1]", solcat_program::ID),
+            format!("Program data: {}", STANDARD.encode(event.data())),
+            format!("Program {} success", solcat_program::ID),
+        ];
+        let first = Signature::new_unique();
+        rpc.push_transaction_logs(first, 100, logs.clone());
+
+        let mut indexer = Indexer::new(Connection::open_in_memory().unwrap()).unwrap();
+        assert_eq!(indexer.sync(&rpc).unwrap().events, 1);
+        assert_eq!(indexer.sync(&rpc).unwrap().events, 0);
+        assert_eq!(indexer.last_signature().unwrap(), Some(first));
+
+        rpc.push_transaction_logs(Signature::new_unique(), 200, logs);
+        assert_eq!(indexer.sync(&rpc).unwrap().events, 1);
+        let rows = indexer.store().query("SELECT name FROM events ORDER BY block_time", &[]).unwrap();
+        assert_eq!(rows, vec![vec!["ReportUpdated".into()]; 2]);
+    }
+}
@@ -0,0 +1,13 @@
+// Materializes SOLCAT program accounts and events into SQLite or Postgres tables,
+// from a snapshot directory of account files or a running validator
+
+pub mod error;
+pub mod index;
+pub mod schema;
+pub mod snapshot;
+pub mod store;
+
+pub use error::{IndexerError, Result};
+pub use index::{IndexStats, Indexer};
+pub use snapshot::read_snapshot_dir;
+pub use store::{connect, Row, SqlValue, Store};
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use solcat_indexer::{connect, read_snapshot_dir, Indexer};
+
+#[derive(Parser)]
+#[command(name = "solcat-indexer", version, about = "Index SOLCAT accounts and events into SQLite or Postgres")]
+struct Cli {
+    /// SQLite database file or postgres:// URL
+    #[arg(long, short = 'd', global = true, env = "SOLCAT_DATABASE_URL", default_value = "solcat.db")]
+    database: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index a directory of account files as written by `solana account --output json`
+    Snapshot {
+        dir: PathBuf,
+        /// Slot the accounts were dumped at
+        #[arg(long)]
+        slot: u64,
+        /// The directory holds only some accounts, keep indexed rows it lacks
+        #[arg(long)]
+        partial: bool,
+    },
+    /// Index the program accounts and new transactions of a validator
+    Sync {
+        /// RPC endpoint of the validator
+        #[arg(long, short = 'u', env = "SOLCAT_RPC_URL", default_value = "http://127.0.0.1:8899")]
+        url: String,
+        /// Keep syncing, pausing this many seconds between syncs
+        #[arg(long)]
+        interval: Option<u64>,
+    },
+}
+
+fn run(cli: Cli) -> anyhow::Result<()> {
+    let store = connect(&cli.database).with_context(|| format!("opening {}", cli.database))?;
+    let mut indexer = Indexer::new(store)?;
+
+    match cli.command {
+        Command::Snapshot { dir, slot, partial } => {
+            let accounts = read_snapshot_dir(&dir)?;
+            let stats = indexer.index_snapshot(slot, &accounts, !partial)?;
+            println!("slot {}: {}", slot, stats);
+        }
+        Command::Sync { url, interval } => {
+            let rpc = RpcClient::new_with_commitment(url, CommitmentConfig::confirmed());
+            loop {
+                let stats = indexer.sync(&rpc)?;
+                println!("{}", stats);
+                match interval {
+                    Some(seconds) => thread::sleep(Duration::from_secs(seconds)),
+                    None => break,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
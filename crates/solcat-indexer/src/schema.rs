@@ -0,0 +1,158 @@
+// Tables of the index, valid on SQLite and Postgres alike.
+// Every account table is keyed by account address and records the slot the row was
+// read at, so ingesting the same or an older state of an account changes nothing.
+// Amounts are BIGINT, u128 accumulators are decimal TEXT and lists of enums are JSON arrays.
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS reports (
+    account TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    reporter TEXT NOT NULL,
+    reported_address TEXT NOT NULL,
+    risk_score BIGINT NOT NULL,
+    stake_amount BIGINT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    description TEXT NOT NULL,
+    vote_weight BIGINT NOT NULL,
+    last_update_time BIGINT NOT NULL,
+    time_lock_end BIGINT NOT NULL,
+    base_score BIGINT NOT NULL,
+    risk_types TEXT NOT NULL,
+    confidence_score BIGINT NOT NULL,
+    evidence_count BIGINT NOT NULL,
+    assessment_updated_at BIGINT NOT NULL,
+    transaction_volume BIGINT NOT NULL,
+    unique_interactions BIGINT NOT NULL,
+    age_of_account BIGINT NOT NULL,
+    suspicious_patterns TEXT NOT NULL,
+    verified BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS reports_reported_address ON reports (reported_address);
+CREATE INDEX IF NOT EXISTS reports_reporter ON reports (reporter);
+
+CREATE TABLE IF NOT EXISTS address_stats (
+    account TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    address TEXT NOT NULL,
+    total_reports BIGINT NOT NULL,
+    total_stake BIGINT NOT NULL,
+    last_update BIGINT NOT NULL,
+    weighted_risk_score TEXT NOT NULL,
+    total_vote_weight BIGINT NOT NULL,
+    total_confidence BIGINT NOT NULL,
+    distinct_reporters BIGINT NOT NULL,
+    aggregate_score BIGINT NOT NULL,
+    confidence BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS address_stats_address ON address_stats (address);
+
+CREATE TABLE IF NOT EXISTS reporter_stats (
+    account TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    reporter TEXT,
+    total_reports BIGINT NOT NULL,
+    successful_reports BIGINT NOT NULL,
+    total_stake BIGINT NOT NULL,
+    reputation_score BIGINT NOT NULL,
+    last_report_time BIGINT NOT NULL,
+    reports_in_window BIGINT NOT NULL,
+    cooldown_end_time BIGINT NOT NULL,
+    token_balance BIGINT NOT NULL,
+    rewards_claimed BIGINT NOT NULL,
+    reward_points BIGINT NOT NULL,
+    points_epoch BIGINT NOT NULL,
+    delegated_stake BIGINT NOT NULL,
+    delegation_shares BIGINT NOT NULL,
+    commission_bps BIGINT NOT NULL,
+    delegation_reward_per_share TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS reporter_stats_reporter ON reporter_stats (reporter);
+
+CREATE TABLE IF NOT EXISTS stakes (
+    account TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    owner TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    amount BIGINT NOT NULL,
+    weight BIGINT NOT NULL,
+    rewards_earned BIGINT NOT NULL,
+    reward_per_token_paid TEXT NOT NULL,
+    lock_end_time BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS stakes_owner ON stakes (owner);
+
+CREATE TABLE IF NOT EXISTS batches (
+    account TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    reporter TEXT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    verification_status TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS batches_reporter ON batches (reporter);
+
+CREATE TABLE IF NOT EXISTS batch_entries (
+    batch TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    address TEXT NOT NULL,
+    risk_score BIGINT NOT NULL,
+    PRIMARY KEY (batch, position)
+);
+CREATE INDEX IF NOT EXISTS batch_entries_address ON batch_entries (address);
+
+CREATE TABLE IF NOT EXISTS histories (
+    account TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    address TEXT NOT NULL,
+    listing_status TEXT NOT NULL,
+    blacklist_reason TEXT NOT NULL,
+    blacklist_timestamp BIGINT NOT NULL,
+    blacklist_expiry BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS histories_address ON histories (address);
+
+CREATE TABLE IF NOT EXISTS history_reports (
+    history TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    risk_score BIGINT NOT NULL,
+    reporter TEXT NOT NULL,
+    description TEXT NOT NULL,
+    PRIMARY KEY (history, position)
+);
+
+CREATE TABLE IF NOT EXISTS blacklist_log (
+    history TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    action TEXT NOT NULL,
+    status TEXT NOT NULL,
+    authority TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    expiry BIGINT NOT NULL,
+    PRIMARY KEY (history, position)
+);
+CREATE INDEX IF NOT EXISTS blacklist_log_timestamp ON blacklist_log (timestamp);
+
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    block_time BIGINT,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (signature, position)
+);
+CREATE INDEX IF NOT EXISTS events_slot ON events (slot);
+
+CREATE TABLE IF NOT EXISTS sync_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+// Account tables and the tables of rows they own, by owning column
+pub const ACCOUNT_TABLES: [&str; 6] = ["reports", "address_stats", "reporter_stats", "stakes", "batches", "histories"];
+pub const CHILD_TABLES: [(&str, &str, &str); 3] = [
+    ("batch_entries", "batch", "batches"),
+    ("history_reports", "history", "histories"),
+    ("blacklist_log", "history", "histories"),
+];
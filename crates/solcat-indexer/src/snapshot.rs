@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{IndexerError, Result};
+
+// An account file as written by `solana account --output json` and read by
+// `solana-test-validator --account-dir`
+#[derive(Deserialize)]
+struct AccountFile {
+    pubkey: String,
+    account: UiAccount,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiAccount {
+    lamports: u64,
+    data: (String, String),
+    owner: String,
+    executable: bool,
+    rent_epoch: u64,
+}
+
+// Reads every `.json` account file of a snapshot directory, in file name order
+pub fn read_snapshot_dir(dir: &Path) -> Result<Vec<(Pubkey, Account)>> {
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| IndexerError::Io { path, source }
+    };
+
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).map_err(io_error(dir))? {
+        let path = entry.map_err(io_error(dir))?.path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let contents = fs::read(&path).map_err(io_error(&path))?;
+            read_account_file(&contents).map_err(|reason| IndexerError::InvalidSnapshot { path, reason })
+        })
+        .collect()
+}
+
+fn read_account_file(contents: &[u8]) -> std::result::Result<(Pubkey, Account), String> {
+    let file: AccountFile = serde_json::from_slice(contents).map_err(|err| err.to_string())?;
+    let (data, encoding) = file.account.data;
+    if encoding != "base64" {
+        return Err(format!("unsupported data encoding {:?}, expected base64", encoding));
+    }
+
+    let address = file.pubkey.parse::<Pubkey>().map_err(|_| format!("invalid pubkey {:?}", file.pubkey))?;
+    let owner = file.account.owner.parse::<Pubkey>().map_err(|_| format!("invalid owner {:?}", file.account.owner))?;
+    let account = Account {
+        lamports: file.account.lamports,
+        data: STANDARD.decode(data).map_err(|err| err.to_string())?,
+        owner,
+        executable: file.account.executable,
+        rent_epoch: file.account.rent_epoch,
+    };
+    Ok((address, account))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_account_files_in_name_order() {
+        let dir = std::env::temp_dir().join(format!("solcat-snapshot-{}", Pubkey::new_unique()));
+        fs::create_dir_all(&dir).unwrap();
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        for (name, address, data) in [("b.json", second, "AQID"), ("a.json", first, "")] {
+            let file = format!(
+                r#"{{"pubkey": "{address}", "account": {{"lamports": 5, "data": ["{data}", "base64"],
+                "owner": "{owner}", "executable": false, "rentEpoch": 0, "space": 3}}}}"#,
+                owner = solcat_client::solcat_program::ID,
+            );
+            fs::write(dir.join(name), file).unwrap();
+        }
+        fs::write(dir.join("notes.txt"), "not an account").unwrap();
+
+        let accounts = read_snapshot_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(accounts.iter().map(|(address, _)| *address).collect::<Vec<_>>(), vec![first, second]);
+        assert_eq!(accounts[1].1.data, vec![1, 2, 3]);
+        assert_eq!(accounts[1].1.owner, solcat_client::solcat_program::ID);
+    }
+}
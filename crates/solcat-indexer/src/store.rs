@@ -0,0 +1,267 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{IndexerError, Result};
+
+// A value bound to or read from a statement, the schema only uses integers and text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Text(String),
+}
+
+impl SqlValue {
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            SqlValue::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SqlValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl From<i64> for SqlValue {
+    fn from(value: i64) -> Self {
+        SqlValue::Integer(value)
+    }
+}
+
+// BIGINT columns hold up to i64::MAX, a larger amount is an error rather than a wrong value
+impl TryFrom<u64> for SqlValue {
+    type Error = IndexerError;
+
+    fn try_from(value: u64) -> Result<Self> {
+        i64::try_from(value).map(SqlValue::Integer).map_err(|_| IndexerError::ValueOutOfRange(value))
+    }
+}
+
+impl From<u32> for SqlValue {
+    fn from(value: u32) -> Self {
+        SqlValue::Integer(value.into())
+    }
+}
+
+impl From<u16> for SqlValue {
+    fn from(value: u16) -> Self {
+        SqlValue::Integer(value.into())
+    }
+}
+
+impl From<u8> for SqlValue {
+    fn from(value: u8) -> Self {
+        SqlValue::Integer(value.into())
+    }
+}
+
+impl From<bool> for SqlValue {
+    fn from(value: bool) -> Self {
+        SqlValue::Integer(value.into())
+    }
+}
+
+impl From<u128> for SqlValue {
+    fn from(value: u128) -> Self {
+        SqlValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for SqlValue {
+    fn from(value: String) -> Self {
+        SqlValue::Text(value)
+    }
+}
+
+impl From<&str> for SqlValue {
+    fn from(value: &str) -> Self {
+        SqlValue::Text(value.to_string())
+    }
+}
+
+impl From<Pubkey> for SqlValue {
+    fn from(value: Pubkey) -> Self {
+        SqlValue::Text(value.to_string())
+    }
+}
+
+impl<T: Into<SqlValue>> From<Option<T>> for SqlValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(SqlValue::Null, Into::into)
+    }
+}
+
+pub type Row = Vec<SqlValue>;
+
+// The database the index is kept in. Statements number their parameters `$1`, `$2`, ...
+// on every backend.
+pub trait Store {
+    fn execute_batch(&mut self, sql: &str) -> Result<()>;
+
+    // Number of rows changed
+    fn execute(&mut self, sql: &str, params: &[SqlValue]) -> Result<u64>;
+
+    fn query(&mut self, sql: &str, params: &[SqlValue]) -> Result<Vec<Row>>;
+}
+
+impl<S: Store + ?Sized> Store for Box<S> {
+    fn execute_batch(&mut self, sql: &str) -> Result<()> {
+        (**self).execute_batch(sql)
+    }
+
+    fn execute(&mut self, sql: &str, params: &[SqlValue]) -> Result<u64> {
+        (**self).execute(sql, params)
+    }
+
+    fn query(&mut self, sql: &str, params: &[SqlValue]) -> Result<Vec<Row>> {
+        (**self).query(sql, params)
+    }
+}
+
+// Opens `postgres://` and `postgresql://` URLs with Postgres, anything else as a
+// SQLite database file, optionally prefixed with `sqlite:`
+pub fn connect(url: &str) -> Result<Box<dyn Store + Send>> {
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        #[cfg(feature = "postgres")]
+        return Ok(Box::new(postgres::Client::connect(url, postgres::NoTls)?));
+    } else {
+        #[cfg(feature = "sqlite")]
+        return Ok(Box::new(rusqlite::Connection::open(url.strip_prefix("sqlite:").unwrap_or(url))?));
+    }
+    #[allow(unreachable_code)]
+    Err(IndexerError::UnsupportedDatabase(url.to_string()))
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::borrow::Cow;
+
+    use rusqlite::types::{ToSqlOutput, ValueRef};
+    use rusqlite::{params_from_iter, Connection, ToSql};
+
+    use super::*;
+
+    impl ToSql for SqlValue {
+        fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+            Ok(ToSqlOutput::Borrowed(match self {
+                SqlValue::Null => ValueRef::Null,
+                SqlValue::Integer(value) => ValueRef::Integer(*value),
+                SqlValue::Text(value) => ValueRef::Text(value.as_bytes()),
+            }))
+        }
+    }
+
+    // SQLite reads `$1` as a named parameter, `?1` is the numbered one
+    fn numbered_parameters(sql: &str) -> Cow<'_, str> {
+        if !sql.contains('$') {
+            return Cow::Borrowed(sql);
+        }
+        let mut numbered = String::with_capacity(sql.len());
+        let mut chars = sql.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '$' && chars.peek().is_some_and(char::is_ascii_digit) {
+                numbered.push('?');
+            } else {
+                numbered.push(c);
+            }
+        }
+        Cow::Owned(numbered)
+    }
+
+    impl Store for Connection {
+        fn execute_batch(&mut self, sql: &str) -> Result<()> {
+            Ok(Connection::execute_batch(self, sql)?)
+        }
+
+        fn execute(&mut self, sql: &str, params: &[SqlValue]) -> Result<u64> {
+            let mut statement = self.prepare_cached(&numbered_parameters(sql))?;
+            Ok(statement.execute(params_from_iter(params))? as u64)
+        }
+
+        fn query(&mut self, sql: &str, params: &[SqlValue]) -> Result<Vec<Row>> {
+            let mut statement = self.prepare_cached(&numbered_parameters(sql))?;
+            let names: Vec<String> = statement.column_names().into_iter().map(str::to_string).collect();
+            let mut rows = statement.query(params_from_iter(params))?;
+
+            let mut result = Vec::new();
+            while let Some(row) = rows.next()? {
+                let mut values = Vec::with_capacity(names.len());
+                for (index, name) in names.iter().enumerate() {
+                    values.push(match row.get_ref(index)? {
+                        ValueRef::Null => SqlValue::Null,
+                        ValueRef::Integer(value) => SqlValue::Integer(value),
+                        ValueRef::Text(text) => SqlValue::Text(String::from_utf8_lossy(text).into_owned()),
+                        ValueRef::Real(_) | ValueRef::Blob(_) => return Err(IndexerError::UnsupportedColumn(name.clone())),
+                    });
+                }
+                result.push(values);
+            }
+            Ok(result)
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod pg {
+    use bytes::BytesMut;
+    use postgres::types::{to_sql_checked, IsNull, ToSql, Type};
+    use postgres::Client;
+
+    use super::*;
+
+    type BoxedError = Box<dyn std::error::Error + Sync + Send>;
+
+    impl ToSql for SqlValue {
+        fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> std::result::Result<IsNull, BoxedError> {
+            match (self, ty) {
+                (SqlValue::Null, _) => Ok(IsNull::Yes),
+                (SqlValue::Integer(value), &Type::INT8) => value.to_sql(ty, out),
+                (SqlValue::Integer(value), &Type::INT4) => i32::try_from(*value)?.to_sql(ty, out),
+                (SqlValue::Integer(value), &Type::INT2) => i16::try_from(*value)?.to_sql(ty, out),
+                (SqlValue::Text(value), &Type::TEXT | &Type::VARCHAR) => value.to_sql(ty, out),
+                (value, ty) => Err(format!("cannot bind {:?} to a {} parameter", value, ty).into()),
+            }
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            matches!(*ty, Type::INT2 | Type::INT4 | Type::INT8 | Type::TEXT | Type::VARCHAR)
+        }
+
+        to_sql_checked!();
+    }
+
+    impl Store for Client {
+        fn execute_batch(&mut self, sql: &str) -> Result<()> {
+            Ok(self.batch_execute(sql)?)
+        }
+
+        fn execute(&mut self, sql: &str, params: &[SqlValue]) -> Result<u64> {
+            let params: Vec<&(dyn ToSql + Sync)> = params.iter().map(|param| param as _).collect();
+            Ok(Client::execute(self, sql, &params)?)
+        }
+
+        fn query(&mut self, sql: &str, params: &[SqlValue]) -> Result<Vec<Row>> {
+            let params: Vec<&(dyn ToSql + Sync)> = params.iter().map(|param| param as _).collect();
+            let mut result = Vec::new();
+            for row in Client::query(self, sql, &params)? {
+                let mut values = Vec::with_capacity(row.len());
+                for (index, column) in row.columns().iter().enumerate() {
+                    let value = match *column.type_() {
+                        Type::INT8 => row.try_get::<_, Option<i64>>(index)?.map(SqlValue::Integer),
+                        Type::INT4 => row.try_get::<_, Option<i32>>(index)?.map(|value| SqlValue::Integer(value.into())),
+                        Type::INT2 => row.try_get::<_, Option<i16>>(index)?.map(|value| SqlValue::Integer(value.into())),
+                        Type::TEXT | Type::VARCHAR => row.try_get::<_, Option<String>>(index)?.map(SqlValue::Text),
+                        _ => return Err(IndexerError::UnsupportedColumn(column.name().to_string())),
+                    };
+                    values.push(value.unwrap_or(SqlValue::Null));
+                }
+                result.push(values);
+            }
+            Ok(result)
+        }
+    }
+}
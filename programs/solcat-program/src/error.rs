@@ -1,80 +1,91 @@
-use solana_program::program_error::ProgramError;
-use thiserror::Error;
+use anchor_lang::prelude::*;
 
-#[derive(Error, Debug, Copy, Clone)]
+#[error_code]
 pub enum SolcatError {
-    #[error("Invalid address format")]
+    #[msg("Invalid address format")]
     InvalidAddress,
     
-    #[error("Invalid risk score")]
+    #[msg("Invalid risk score")]
     InvalidRiskScore,
     
-    #[error("Insufficient stake")]
+    #[msg("Insufficient stake")]
     InsufficientStake,
     
-    #[error("Report already exists")]
+    #[msg("Report already exists")]
     ReportAlreadyExists,
     
-    #[error("Not authorized")]
+    #[msg("Not authorized")]
     NotAuthorized,
     
-    #[error("Invalid report data")]
+    #[msg("Invalid report data")]
     InvalidReportData,
 
-    #[error("Insufficient reputation")]
+    #[msg("Insufficient reputation")]
     InsufficientReputation,
 
-    #[error("Time lock active")]
+    #[msg("Time lock active")]
     TimeLockActive,
 
-    #[error("Invalid vote weight")]
+    #[msg("Invalid vote weight")]
     InvalidVoteWeight,
 
-    #[error("Report limit exceeded")]
+    #[msg("Report limit exceeded")]
     ReportLimitExceeded,
 
-    #[error("Cooldown period active")]
+    #[msg("Cooldown period active")]
     CooldownActive,
 
-    #[error("Insufficient token balance")]
+    #[msg("Insufficient token balance")]
     InsufficientTokenBalance,
 
-    #[error("Invalid token mint")]
+    #[msg("Invalid token mint")]
     InvalidTokenMint,
 
-    #[error("Staking disabled")]
+    #[msg("Staking disabled")]
     StakingDisabled,
 
-    #[error("Invalid stake amount")]
+    #[msg("Invalid stake amount")]
     InvalidStakeAmount,
 
-    #[error("Stake locked")]
+    #[msg("Stake locked")]
     StakeLocked,
 
-    #[error("Invalid reward calculation")]
+    #[msg("Invalid reward calculation")]
     InvalidRewardCalculation,
 
-    #[error("Treasury account mismatch")]
+    #[msg("Treasury account mismatch")]
     TreasuryMismatch,
 
-    #[error("Invalid batch report")]
+    #[msg("Invalid batch report")]
     InvalidBatchReport,
 
-    #[error("Batch verification pending")]
+    #[msg("Batch verification pending")]
     BatchVerificationPending,
 
-    #[error("Address already blacklisted")]
+    #[msg("Address already blacklisted")]
     AddressAlreadyBlacklisted,
 
-    #[error("Invalid blacklist operation")]
+    #[msg("Invalid blacklist operation")]
     InvalidBlacklistOperation,
 
-    #[error("History update failed")]
+    #[msg("History update failed")]
     HistoryUpdateFailed,
-}
 
-impl From<SolcatError> for ProgramError {
-    fn from(e: SolcatError) -> Self {
-        ProgramError::Custom(e as u32)
-    }
-} 
\ No newline at end of file
+    #[msg("Blacklist threshold not met")]
+    BlacklistThresholdNotMet,
+
+    #[msg("Risk threshold exceeded")]
+    RiskThresholdExceeded,
+
+    #[msg("Unbonding period active")]
+    UnbondingActive,
+
+    #[msg("Emission cap exceeded")]
+    EmissionCapExceeded,
+
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+
+    #[msg("Invalid listing threshold")]
+    InvalidListingThreshold,
+}
@@ -0,0 +1,358 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::state::{BlacklistAction, ListingStatus};
+
+// Events are logged by `emit!` as `Program data: <base64>` lines holding the
+// 8-byte event discriminator followed by the Borsh-encoded fields.
+// The layout is part of the program's interface: new fields go at the end
+// of an event and existing fields are never reordered or retyped.
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigUpdated {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub stake_vault: Pubkey,
+    pub staking_enabled: bool,
+    pub min_stake_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressReported {
+    pub report: Pubkey,
+    pub reporter: Pubkey,
+    pub reported_address: Pubkey,
+    pub risk_score: u8,
+    pub base_score: u8,
+    pub confidence_score: u8,
+    pub vote_weight: u64,
+    pub stake_amount: u64,
+    // Set for low-stake flags from mark_suspicious_address
+    pub quick_flag: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportUpdated {
+    pub report: Pubkey,
+    pub reporter: Pubkey,
+    pub reported_address: Pubkey,
+    pub risk_score: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportRemoved {
+    pub report: Pubkey,
+    pub reporter: Pubkey,
+    pub reported_address: Pubkey,
+    pub stake_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StakeAdded {
+    pub report: Pubkey,
+    pub staker: Pubkey,
+    pub reported_address: Pubkey,
+    pub amount: u64,
+    pub vote_weight: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReporterStatsUpdated {
+    pub reporter: Pubkey,
+    pub reputation_score: u8,
+    // The report credited by this update, if any, and the points it earned
+    pub verified_report: Option<Pubkey>,
+    pub points: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokensStaked {
+    pub owner: Pubkey,
+    pub user_stake: Pubkey,
+    pub amount: u64,
+    pub lock_end_time: i64,
+    pub weight: u64,
+    pub timestamp: i64,
+}
+
+// Tokens leaving a stake position or delegation into unbonding
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnbondingQueued {
+    pub owner: Pubkey,
+    pub source: Pubkey,
+    pub unbonding: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub release_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnbondedWithdrawn {
+    pub owner: Pubkey,
+    pub unbonding: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnbondingSlashed {
+    pub owner: Pubkey,
+    pub unbonding: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RewardKind {
+    Staking,
+    Reporter,
+    Delegation,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardsClaimed {
+    pub owner: Pubkey,
+    // The user stake, reporter stats or delegation the rewards were earned by
+    pub source: Pubkey,
+    pub kind: RewardKind,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardsCompounded {
+    pub owner: Pubkey,
+    pub user_stake: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardsDistributed {
+    pub epoch: u64,
+    pub reward_per_token: u128,
+    pub total_distributed: u64,
+    pub emission_cap: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StakeWeightRefreshed {
+    pub user_stake: Pubkey,
+    pub weight: u64,
+    pub refresher: Pubkey,
+    pub bounty: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSubmitted {
+    pub batch_report: Pubkey,
+    pub reporter: Pubkey,
+    pub addresses: Vec<Pubkey>,
+    pub risk_scores: Vec<u8>,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchVerified {
+    pub batch_report: Pubkey,
+    pub verifier: Pubkey,
+    pub reporter: Pubkey,
+    pub verified: bool,
+    pub points: u64,
+    pub timestamp: i64,
+}
+
+// Any move to a listing tier, manual or by threshold evaluation
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressBlacklisted {
+    pub address: Pubkey,
+    pub status: ListingStatus,
+    pub action: BlacklistAction,
+    pub authority: Pubkey,
+    pub reason: String,
+    pub expiry: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressUnblacklisted {
+    pub address: Pubkey,
+    pub authority: Pubkey,
+    pub rationale: String,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryUpdated {
+    pub address: Pubkey,
+    pub report: Pubkey,
+    pub risk_score: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReporterRewardsFunded {
+    pub epoch: u64,
+    pub budget: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StakeDelegated {
+    pub delegator: Pubkey,
+    pub reporter: Pubkey,
+    pub delegation: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StakeUndelegated {
+    pub delegator: Pubkey,
+    pub reporter: Pubkey,
+    pub delegation: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommissionSet {
+    pub reporter: Pubkey,
+    pub commission_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelegationSlashed {
+    pub reporter: Pubkey,
+    pub reporter_part: u64,
+    pub delegators_part: u64,
+    pub timestamp: i64,
+}
+
+macro_rules! solcat_events {
+    ($($event:ident),* $(,)?) => {
+        // Every event the program emits, for decoders that read them back from logs
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum SolcatEvent {
+            $($event($event),)*
+        }
+
+        impl SolcatEvent {
+            // Decode the bytes of a `Program data:` log line, None for events of other programs
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                if data.len() < 8 {
+                    return None;
+                }
+                let (discriminator, mut fields) = data.split_at(8);
+                $(
+                    if discriminator == $event::DISCRIMINATOR {
+                        return $event::deserialize(&mut fields).ok().map(SolcatEvent::$event);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(SolcatEvent::$event(_) => stringify!($event),)*
+                }
+            }
+
+            // The bytes the event was logged as, discriminator first
+            pub fn data(&self) -> Vec<u8> {
+                match self {
+                    $(SolcatEvent::$event(event) => anchor_lang::Event::data(event),)*
+                }
+            }
+        }
+    };
+}
+
+solcat_events!(
+    ConfigUpdated,
+    AddressReported,
+    ReportUpdated,
+    ReportRemoved,
+    StakeAdded,
+    ReporterStatsUpdated,
+    TokensStaked,
+    UnbondingQueued,
+    UnbondedWithdrawn,
+    UnbondingSlashed,
+    RewardsClaimed,
+    RewardsCompounded,
+    RewardsDistributed,
+    StakeWeightRefreshed,
+    BatchSubmitted,
+    BatchVerified,
+    AddressBlacklisted,
+    AddressUnblacklisted,
+    HistoryUpdated,
+    ReporterRewardsFunded,
+    StakeDelegated,
+    StakeUndelegated,
+    CommissionSet,
+    DelegationSlashed,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+
+    #[test]
+    fn decodes_emitted_event_data() {
+        let event = BatchSubmitted {
+            batch_report: Pubkey::new_unique(),
+            reporter: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            risk_scores: vec![40, 90],
+            timestamp: 1_700_000_000,
+        };
+
+        assert_eq!(SolcatEvent::decode(&event.data()), Some(SolcatEvent::BatchSubmitted(event)));
+    }
+
+    #[test]
+    fn ignores_unknown_discriminators() {
+        assert_eq!(SolcatEvent::decode(&[0; 16]), None);
+        assert_eq!(SolcatEvent::decode(&[1, 2, 3]), None);
+    }
+}
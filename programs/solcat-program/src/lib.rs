@@ -1,39 +1,64 @@
-use solana_program::{
-    account_info::{next_account_info, AccountInfo},
-    entrypoint,
-    entrypoint::ProgramResult,
-    msg,
-    program_error::ProgramError,
-    pubkey::Pubkey,
-    clock::Clock,
-    sysvar::Sysvar,
-};
+#![allow(clippy::result_large_err)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Token, TokenAccount};
 
-mod error;
-mod state;
+pub mod error;
+pub mod events;
+pub mod state;
 
 use error::SolcatError;
+use events::*;
 use state::{
     AddressReport, AddressStats, ReporterStats, GlobalConfig, UserStake, StakePool,
-    ReportHistory, BatchReport, VerificationStatus, HistoricalReport, RiskAssessment, RiskMetrics
+    ReportHistory, BatchReport, VerificationStatus, HistoricalReport, RiskAssessment, RiskMetrics, RiskType,
+    BlacklistAction, BlacklistRecord, ListingStatus, AddressRiskSummary,
+    ADDRESS_STATS_SEED, REPORT_HISTORY_SEED, ADDRESS_STATS_SPACE, REPORT_HISTORY_SPACE,
+    USER_STAKE_SEED, USER_STAKE_SPACE, VAULT_AUTHORITY_SEED, PenaltyDestination,
+    Unbonding, UNBONDING_SEED, UNBONDING_SPACE, ReporterRewardPool,
+    REPORTER_STATS_SEED, REPORTER_STATS_SPACE, REPORTER_REWARD_POOL_SEED, REPORTER_REWARD_POOL_SPACE,
+    Delegation, DELEGATION_SEED, DELEGATION_SPACE,
+    CONFIG_SEED, GLOBAL_CONFIG_SPACE, STAKE_POOL_SEED, STAKE_POOL_SPACE,
+    ADDRESS_REPORT_SPACE, MAX_DESCRIPTION_LEN, MAX_BLACKLIST_REASON_LEN, MAX_RISK_TYPES, MAX_SUSPICIOUS_PATTERNS, MAX_PATTERN_LEN,
+    BATCH_REPORT_SPACE, MAX_BATCH_SIZE, SUSPICIOUS_SEED,
+    find_address_stats_address, find_suspicious_address, find_report_history_address,
 };
 
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
 // Constants for anti-Sybil mechanisms
-const MIN_REPUTATION_SCORE: u8 = 10;
-const REPORT_COOLDOWN_PERIOD: i64 = 3600; // 1 hour in seconds
-const MAX_REPORTS_PER_WINDOW: u32 = 5;
-const REPORT_WINDOW_DURATION: i64 = 86400; // 24 hours in seconds
+pub const MIN_REPUTATION_SCORE: u8 = 10;
+pub const REPORT_COOLDOWN_PERIOD: i64 = 3600; // 1 hour in seconds
+pub const MAX_REPORTS_PER_WINDOW: u32 = 5;
+pub const REPORT_WINDOW_DURATION: i64 = 86400; // 24 hours in seconds
 const TIME_LOCK_DURATION: i64 = 604800; // 7 days in seconds
 
 // Constants for token economics
-const REWARD_MULTIPLIER: u64 = 100; // Base reward multiplier
+const REWARD_MULTIPLIER: u64 = 100; // Base reward multiplier, in percent of staked amount
 const MIN_STAKE_DURATION: i64 = 604800; // 7 days in seconds
 const MAX_STAKE_DURATION: i64 = 31536000; // 365 days in seconds
-const STAKE_DURATION_MULTIPLIER: u64 = 10; // Multiplier for longer stake duration
+const STAKE_DURATION_MULTIPLIER: u64 = 300; // Extra percent of weight for a full MAX_STAKE_DURATION lock
+
+const BASIS_POINTS: u64 = 10_000;
+
+// Constants for vote weight, a report weighs at most
+// (VOTE_WEIGHT_BASE + MAX_REPUTATION_WEIGHT) * (1 + MAX_STAKE_WEIGHT)
+const VOTE_WEIGHT_BASE: u64 = 100;
+const MAX_REPUTATION_WEIGHT: u64 = 100;
+const MAX_STAKE_WEIGHT: u64 = 100; // Square roots of whole SOL staked plus whole tokens delegated
+
+// Constants for reporter rewards
+const REPORT_POINTS_MAX_STAKE_MULTIPLIER: u64 = 10;
+
+// Constants for quick flags, which trade reputation and evidence for a small stake
+const QUICK_FLAG_STAKE: u64 = 10_000_000; // 0.01 SOL
+const QUICK_FLAG_RISK_SCORE: u8 = 50;
+const QUICK_FLAG_CONFIDENCE: u8 = 25;
 
 // Constants for batch reporting
-const MAX_BATCH_SIZE: usize = 10;
 const MIN_VERIFICATION_STAKE: u64 = 100_000_000; // 0.1 SOL
+const MIN_VERIFIER_REPUTATION: u8 = 50;
 
 // Constants for risk assessment
 const RISK_WEIGHT_TRANSACTION_VOLUME: f32 = 0.3;
@@ -41,805 +66,2271 @@ const RISK_WEIGHT_INTERACTIONS: f32 = 0.2;
 const RISK_WEIGHT_ACCOUNT_AGE: f32 = 0.1;
 const RISK_WEIGHT_PATTERNS: f32 = 0.4;
 
-entrypoint!(process_instruction);
+#[program]
+pub mod solcat_program {
+    use super::*;
 
-pub fn process_instruction(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    instruction_data: &[u8],
-) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let instruction = instruction_data[0];
-
-    match instruction {
-        0 => report_address(program_id, accounts_iter, &instruction_data[1..]),
-        1 => update_report(program_id, accounts_iter, &instruction_data[1..]),
-        2 => stake_on_report(program_id, accounts_iter, &instruction_data[1..]),
-        3 => update_reporter_stats(program_id, accounts_iter, &instruction_data[1..]),
-        4 => stake_tokens(program_id, accounts_iter, &instruction_data[1..]),
-        5 => unstake_tokens(program_id, accounts_iter, &instruction_data[1..]),
-        6 => claim_rewards(program_id, accounts_iter, &instruction_data[1..]),
-        7 => distribute_rewards(program_id, accounts_iter, &instruction_data[1..]),
-        8 => submit_batch_report(program_id, accounts_iter, &instruction_data[1..]),
-        9 => verify_batch_report(program_id, accounts_iter, &instruction_data[1..]),
-        10 => blacklist_address(program_id, accounts_iter, &instruction_data[1..]),
-        11 => update_history(program_id, accounts_iter, &instruction_data[1..]),
-        _ => Err(ProgramError::InvalidInstructionData),
-    }
-}
-
-fn report_address(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let reporter_info = next_account_info(accounts_iter)?;
-    let reported_address_info = next_account_info(accounts_iter)?;
-    let report_account_info = next_account_info(accounts_iter)?;
-    let stats_account_info = next_account_info(accounts_iter)?;
-    let reporter_stats_info = next_account_info(accounts_iter)?;
-    let clock_sysvar_info = next_account_info(accounts_iter)?;
-
-    // Verify reporter is signer
-    if !reporter_info.is_signer {
-        return Err(SolcatError::NotAuthorized.into());
-    }
-
-    // Check reporter stats and anti-Sybil conditions
-    let mut reporter_stats = ReporterStats::try_from_slice(&reporter_stats_info.data.borrow())?;
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
-    
-    // Check reputation score
-    if reporter_stats.reputation_score < MIN_REPUTATION_SCORE {
-        return Err(SolcatError::InsufficientReputation.into());
+    pub fn initialize(ctx: Context<Initialize>, config: GlobalConfig) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(config.listing_thresholds.is_valid(), SolcatError::InvalidListingThreshold);
+
+        // The pool starts on the configured schedule, with nothing to emit
+        // until distribute_rewards syncs it with the treasury
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        stake_pool.emission_schedule = config.emission_schedule.clone();
+        stake_pool.last_update_time = clock.unix_timestamp;
+
+        ctx.accounts.config.set_inner(config);
+        emit_config_updated(&ctx.accounts.authority, &ctx.accounts.config, clock.unix_timestamp);
+
+        msg!("Program initialized successfully");
+        Ok(())
     }
 
-    // Check cooldown period
-    if clock.unix_timestamp < reporter_stats.cooldown_end_time {
-        return Err(SolcatError::CooldownActive.into());
+    pub fn report_address(
+        ctx: Context<ReportAddress>,
+        risk_score: u8,
+        description: String,
+        risk_types: Vec<RiskType>,
+        confidence_score: u8,
+        evidence_count: u32,
+        risk_metrics: RiskMetrics,
+    ) -> Result<()> {
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        let clock = Clock::get()?;
+
+        // Check reputation score
+        require!(
+            reporter_stats.reputation_score >= MIN_REPUTATION_SCORE,
+            SolcatError::InsufficientReputation
+        );
+
+        check_report_limits(reporter_stats, clock.unix_timestamp)?;
+
+        // Validate report data against the space reserved for the report
+        require!(risk_score <= 100, SolcatError::InvalidRiskScore);
+        require!(description.len() <= MAX_DESCRIPTION_LEN, SolcatError::InvalidReportData);
+        require!(risk_types.len() <= MAX_RISK_TYPES, SolcatError::InvalidReportData);
+        require!(
+            risk_metrics.suspicious_patterns.len() <= MAX_SUSPICIOUS_PATTERNS
+                && risk_metrics.suspicious_patterns.iter().all(|pattern| pattern.len() <= MAX_PATTERN_LEN),
+            SolcatError::InvalidReportData
+        );
+
+        let risk_assessment = RiskAssessment {
+            base_score: risk_score,
+            risk_types,
+            confidence_score,
+            evidence_count,
+            last_update: clock.unix_timestamp,
+        };
+
+        // Calculate final risk score
+        let final_risk_score = calculate_risk_score(&risk_assessment, &risk_metrics);
+        let vote_weight = calculate_vote_weight(reporter_stats)?;
+
+        let reporter = ctx.accounts.reporter.key();
+        let reported_address = ctx.accounts.reported_address.key();
+
+        ctx.accounts.report.set_inner(AddressReport {
+            reporter,
+            reported_address,
+            risk_score: final_risk_score,
+            stake_amount: 0,
+            timestamp: clock.unix_timestamp,
+            description,
+            vote_weight,
+            last_update_time: clock.unix_timestamp,
+            time_lock_end: clock.unix_timestamp + TIME_LOCK_DURATION,
+            risk_assessment,
+            risk_metrics,
+            verified: false,
+        });
+
+        // The first report for an address pays for its stats account
+        record_report(
+            &ctx.accounts.report,
+            &mut ctx.accounts.address_stats,
+            &mut ctx.accounts.reporter_stats,
+            false,
+            clock.unix_timestamp,
+        )?;
+        emit_address_reported(&ctx.accounts.report, false);
+
+        msg!("Address reported successfully");
+        Ok(())
     }
 
-    // Check report limit in time window
-    if clock.unix_timestamp - reporter_stats.last_report_time < REPORT_WINDOW_DURATION {
-        if reporter_stats.reports_in_window >= MAX_REPORTS_PER_WINDOW {
-            return Err(SolcatError::ReportLimitExceeded.into());
+    pub fn mark_suspicious_address(ctx: Context<MarkSuspiciousAddress>, reason: String) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(reason.len() <= MAX_DESCRIPTION_LEN, SolcatError::InvalidReportData);
+
+        // Flagging an address again only amends the reason, it is not a new report
+        let report = &mut ctx.accounts.report;
+        if report.reporter != Pubkey::default() {
+            report.description = reason;
+            report.last_update_time = clock.unix_timestamp;
+            emit!(ReportUpdated {
+                report: report.key(),
+                reporter: report.reporter,
+                reported_address: report.reported_address,
+                risk_score: report.risk_score,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Suspicious address flag amended");
+            return Ok(());
         }
-    } else {
-        // Reset window if it has expired
-        reporter_stats.reports_in_window = 0;
+
+        // A quick flag is backed by a fixed stake instead of reputation, so it carries
+        // no vote weight and only has to respect the cooldown and report window
+        check_report_limits(&mut ctx.accounts.reporter_stats, clock.unix_timestamp)?;
+
+        // Transfer the flag's stake from the reporter to the report account
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.reporter.to_account_info(),
+                    to: ctx.accounts.report.to_account_info(),
+                },
+            ),
+            QUICK_FLAG_STAKE,
+        )?;
+
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        reporter_stats.total_stake = reporter_stats.total_stake
+            .checked_add(QUICK_FLAG_STAKE)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        reporter_stats.quick_flag_stake = reporter_stats.quick_flag_stake
+            .checked_add(QUICK_FLAG_STAKE)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+
+        ctx.accounts.report.set_inner(AddressReport {
+            reporter: ctx.accounts.reporter.key(),
+            reported_address: ctx.accounts.reported_address.key(),
+            risk_score: QUICK_FLAG_RISK_SCORE,
+            stake_amount: QUICK_FLAG_STAKE,
+            timestamp: clock.unix_timestamp,
+            description: reason,
+            vote_weight: 0,
+            last_update_time: clock.unix_timestamp,
+            time_lock_end: clock.unix_timestamp + TIME_LOCK_DURATION,
+            risk_assessment: RiskAssessment {
+                base_score: QUICK_FLAG_RISK_SCORE,
+                risk_types: vec![RiskType::Unknown],
+                confidence_score: QUICK_FLAG_CONFIDENCE,
+                evidence_count: 0,
+                last_update: clock.unix_timestamp,
+            },
+            risk_metrics: RiskMetrics {
+                transaction_volume: 0,
+                unique_interactions: 0,
+                age_of_account: 0,
+                suspicious_patterns: Vec::new(),
+            },
+            verified: false,
+        });
+
+        record_report(
+            &ctx.accounts.report,
+            &mut ctx.accounts.address_stats,
+            &mut ctx.accounts.reporter_stats,
+            true,
+            clock.unix_timestamp,
+        )?;
+        emit_address_reported(&ctx.accounts.report, true);
+
+        msg!("Address flagged as suspicious");
+        Ok(())
     }
 
-    // Parse report data
-    let risk_score = data[0];
-    if risk_score > 100 {
-        return Err(SolcatError::InvalidRiskScore.into());
-    }
-
-    let description_len = data[1] as usize;
-    let description = String::from_utf8_lossy(&data[2..2+description_len]).to_string();
-    
-    // Parse risk assessment data
-    let mut offset = 2 + description_len;
-    let num_risk_types = data[offset] as usize;
-    offset += 1;
-    
-    let mut risk_types = Vec::with_capacity(num_risk_types);
-    for _ in 0..num_risk_types {
-        let risk_type_index = data[offset];
-        offset += 1;
-        risk_types.push(match risk_type_index {
-            0 => RiskType::Scam,
-            1 => RiskType::Phishing,
-            2 => RiskType::Malware,
-            3 => RiskType::Ransomware,
-            4 => RiskType::MoneyLaundering,
-            5 => RiskType::MarketManipulation,
-            _ => RiskType::Unknown,
+    pub fn unmark_suspicious_address(ctx: Context<UnmarkSuspiciousAddress>) -> Result<()> {
+        let report = &ctx.accounts.report;
+        let clock = Clock::get()?;
+
+        // The stake stays at risk for the same time lock as any report,
+        // and a verified flag has already earned its points
+        require!(clock.unix_timestamp >= report.time_lock_end, SolcatError::TimeLockActive);
+        require!(!report.verified, SolcatError::InvalidReportData);
+
+        // Withdraw the flag's contribution, its weight, stake and reporter were never counted
+        let stats = &mut ctx.accounts.address_stats;
+        stats.total_confidence = stats.total_confidence
+            .checked_sub(report.risk_assessment.confidence_score as u64)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        stats.total_reports = stats.total_reports
+            .checked_sub(1)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        stats.remove_risk_score(report.risk_assessment.base_score, report.timestamp);
+        stats.last_update = clock.unix_timestamp;
+
+        // The stake leaves with the account's rent when it is closed to the reporter
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        reporter_stats.total_stake = reporter_stats.total_stake
+            .checked_sub(report.stake_amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        reporter_stats.quick_flag_stake = reporter_stats.quick_flag_stake
+            .checked_sub(report.stake_amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+
+        emit!(ReportRemoved {
+            report: report.key(),
+            reporter: report.reporter,
+            reported_address: report.reported_address,
+            stake_amount: report.stake_amount,
+            timestamp: clock.unix_timestamp,
         });
+
+        msg!("Suspicious address flag removed");
+        Ok(())
     }
 
-    let confidence_score = data[offset];
-    offset += 1;
+    pub fn update_report(ctx: Context<UpdateReport>, risk_score: u8, description: String) -> Result<()> {
+        let report = &mut ctx.accounts.report;
+        let clock = Clock::get()?;
+
+        // Check time lock
+        require!(clock.unix_timestamp >= report.time_lock_end, SolcatError::TimeLockActive);
 
-    let evidence_count = u32::from_le_bytes(data[offset..offset+4].try_into().unwrap());
-    offset += 4;
+        require!(risk_score <= 100, SolcatError::InvalidRiskScore);
+        require!(description.len() <= MAX_DESCRIPTION_LEN, SolcatError::InvalidReportData);
 
-    // Parse risk metrics
-    let transaction_volume = u64::from_le_bytes(data[offset..offset+8].try_into().unwrap());
-    offset += 8;
+        // Update stats, replacing the old score's contribution with the new one
+        let stats = &mut ctx.accounts.address_stats;
+        rescore_report(report, stats, risk_score, clock.unix_timestamp)?;
+        stats.last_update = clock.unix_timestamp;
 
-    let unique_interactions = u32::from_le_bytes(data[offset..offset+4].try_into().unwrap());
-    offset += 4;
+        // Update report
+        report.description = description;
+        report.last_update_time = clock.unix_timestamp;
+        report.time_lock_end = clock.unix_timestamp + TIME_LOCK_DURATION;
 
-    let age_of_account = i64::from_le_bytes(data[offset..offset+8].try_into().unwrap());
-    offset += 8;
+        emit!(ReportUpdated {
+            report: report.key(),
+            reporter: report.reporter,
+            reported_address: report.reported_address,
+            risk_score: report.risk_score,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Report updated successfully");
+        Ok(())
+    }
 
-    let num_patterns = data[offset] as usize;
-    offset += 1;
-    
-    let mut suspicious_patterns = Vec::with_capacity(num_patterns);
-    for _ in 0..num_patterns {
-        let pattern_len = data[offset] as usize;
-        offset += 1;
-        suspicious_patterns.push(
-            String::from_utf8_lossy(&data[offset..offset+pattern_len]).to_string()
+    pub fn stake_on_report(ctx: Context<StakeOnReport>, stake_amount: u64) -> Result<()> {
+        require!(
+            stake_amount >= ctx.accounts.config.min_stake_amount,
+            SolcatError::InsufficientStake
         );
-        offset += pattern_len;
+
+        // A quick flag's account is closed to its reporter on unmark, so it takes no stake from others
+        let report = &ctx.accounts.report;
+        require!(
+            report.key() != find_suspicious_address(ctx.program_id, &report.reported_address, &report.reporter).0,
+            SolcatError::InvalidReportData
+        );
+
+        // Transfer SOL from staker to the report account
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.staker.to_account_info(),
+                    to: ctx.accounts.report.to_account_info(),
+                },
+            ),
+            stake_amount,
+        )?;
+
+        // Update reporter stats, the added stake counts towards the report's weight
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        reporter_stats.total_stake = reporter_stats.total_stake
+            .checked_add(stake_amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        let vote_weight = calculate_vote_weight(reporter_stats)?;
+
+        // Update stats, reweighting this report's contribution
+        let report = &mut ctx.accounts.report;
+        let stats = &mut ctx.accounts.address_stats;
+        stats.total_stake = stats.total_stake
+            .checked_add(stake_amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        stats.weighted_risk_score = replace_weighted_score(
+            stats.weighted_risk_score,
+            report.risk_assessment.base_score,
+            report.vote_weight,
+            report.risk_assessment.base_score,
+            vote_weight,
+        )?;
+        stats.total_vote_weight = stats.total_vote_weight
+            .checked_sub(report.vote_weight)
+            .and_then(|total| total.checked_add(vote_weight))
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+
+        // Update report stake amount
+        report.stake_amount = report.stake_amount
+            .checked_add(stake_amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        report.vote_weight = vote_weight;
+
+        emit!(StakeAdded {
+            report: report.key(),
+            staker: ctx.accounts.staker.key(),
+            reported_address: report.reported_address,
+            amount: stake_amount,
+            vote_weight,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Stake added successfully");
+        Ok(())
     }
 
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
+    pub fn update_reporter_stats(ctx: Context<UpdateReporterStats>, reputation_score: u8) -> Result<()> {
+        // Update reputation score based on successful reports
+        require!(reputation_score <= 100, SolcatError::InvalidReportData);
+
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        reporter_stats.reputation_score = reputation_score;
+
+        // Optionally credit a verified report, which earns reward points for this epoch
+        let (verified_report, points) = match (ctx.accounts.report.as_mut(), ctx.accounts.reward_pool.as_mut()) {
+            (Some(report), Some(reward_pool)) => {
+                if report.reporter != ctx.accounts.reporter.key() || report.verified {
+                    return err!(SolcatError::InvalidReportData);
+                }
+                report.verified = true;
+
+                let severity = report.risk_assessment.risk_types
+                    .iter()
+                    .map(get_risk_type_severity)
+                    .max()
+                    .unwrap_or_else(|| get_risk_type_severity(&RiskType::Unknown));
+                let points = calculate_report_points(severity, reporter_stats.total_stake);
+
+                reward_pool.add_points(reporter_stats, points)?;
+                reporter_stats.successful_reports = reporter_stats.successful_reports
+                    .checked_add(1)
+                    .ok_or(SolcatError::ArithmeticOverflow)?;
+                (Some(report.key()), points)
+            }
+            (None, None) => (None, 0),
+            _ => return Err(ProgramError::NotEnoughAccountKeys.into()),
+        };
+
+        emit!(ReporterStatsUpdated {
+            reporter: ctx.accounts.reporter.key(),
+            reputation_score,
+            verified_report,
+            points,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    let risk_assessment = RiskAssessment {
-        base_score: risk_score,
-        risk_types,
-        confidence_score,
-        evidence_count,
-        last_update: clock.unix_timestamp,
-    };
+        msg!("Reporter stats updated successfully");
+        Ok(())
+    }
 
-    let risk_metrics = RiskMetrics {
-        transaction_volume,
-        unique_interactions,
-        age_of_account,
-        suspicious_patterns,
-    };
+    pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64, duration: i64, position: u32) -> Result<()> {
+        // Verify staking is enabled
+        require!(ctx.accounts.config.staking_enabled, SolcatError::StakingDisabled);
 
-    // Calculate final risk score
-    let final_risk_score = calculate_risk_score(&risk_assessment, &risk_metrics);
-    
-    let report = AddressReport {
-        reporter: *reporter_info.key,
-        reported_address: *reported_address_info.key,
-        risk_score: final_risk_score,
-        stake_amount: 0,
-        timestamp: clock.unix_timestamp,
-        description,
-        vote_weight: calculate_vote_weight(&reporter_stats),
-        last_update_time: clock.unix_timestamp,
-        time_lock_end: clock.unix_timestamp + TIME_LOCK_DURATION,
-        risk_assessment,
-        risk_metrics,
-    };
+        require!(amount > 0, SolcatError::InvalidStakeAmount);
+        require!(
+            (MIN_STAKE_DURATION..=MAX_STAKE_DURATION).contains(&duration),
+            SolcatError::InvalidStakeAmount
+        );
 
-    // Save report
-    report.serialize(&mut *report_account_info.data.borrow_mut())?;
+        let clock = Clock::get()?;
 
-    // Update stats
-    let mut stats = if let Ok(s) = AddressStats::try_from_slice(&stats_account_info.data.borrow()) {
-        s
-    } else {
-        AddressStats {
-            total_reports: 0,
-            risk_scores: Vec::new(),
-            total_stake: 0,
-            last_update: 0,
-            weighted_risk_score: 0,
-            total_vote_weight: 0,
+        // A new position is zeroed on creation
+        let user_stake = &mut ctx.accounts.user_stake;
+        if user_stake.owner == Pubkey::default() {
+            user_stake.owner = ctx.accounts.staker.key();
+            user_stake.position = position;
         }
-    };
 
-    stats.total_reports += 1;
-    stats.risk_scores.push(risk_score);
-    stats.last_update = clock.unix_timestamp;
-    stats.weighted_risk_score += (risk_score as u32) * report.vote_weight;
-    stats.total_vote_weight += report.vote_weight;
+        // Accrue and settle rewards before updating stake
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        stake_pool.accrue(clock.unix_timestamp)?;
+        user_stake.settle(stake_pool)?;
+
+        // Update user stake, a top-up can extend the lock but never shorten it
+        user_stake.amount = user_stake.amount
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        user_stake.lock_end_time = user_stake.lock_end_time.max(clock.unix_timestamp + duration);
+
+        // Update stake pool
+        stake_pool.total_staked = stake_pool.total_staked
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        let weight = calculate_stake_weight(user_stake.amount, user_stake.lock_end_time, clock.unix_timestamp);
+        user_stake.set_weight(stake_pool, weight);
+
+        // Move tokens into the vault
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staker_token_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.staker.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let user_stake = &ctx.accounts.user_stake;
+        emit!(TokensStaked {
+            owner: user_stake.owner,
+            user_stake: user_stake.key(),
+            amount,
+            lock_end_time: user_stake.lock_end_time,
+            weight: user_stake.weight,
+            timestamp: clock.unix_timestamp,
+        });
 
-    stats.serialize(&mut *stats_account_info.data.borrow_mut())?;
+        msg!("Tokens staked successfully");
+        Ok(())
+    }
 
-    // Update reporter stats
-    reporter_stats.total_reports += 1;
-    reporter_stats.last_report_time = clock.unix_timestamp;
-    reporter_stats.reports_in_window += 1;
-    reporter_stats.cooldown_end_time = clock.unix_timestamp + REPORT_COOLDOWN_PERIOD;
+    pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let user_stake = &mut ctx.accounts.user_stake;
 
-    reporter_stats.serialize(&mut *reporter_stats_info.data.borrow_mut())?;
+        // Check lock period
+        require!(clock.unix_timestamp >= user_stake.lock_end_time, SolcatError::StakeLocked);
 
-    msg!("Address reported successfully");
-    Ok(())
-}
+        // Withdrawal may be part of the position
+        require!(amount > 0 && amount <= user_stake.amount, SolcatError::InvalidStakeAmount);
 
-fn update_report(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let reporter_info = next_account_info(accounts_iter)?;
-    let report_account_info = next_account_info(accounts_iter)?;
-    let stats_account_info = next_account_info(accounts_iter)?;
-    let clock_sysvar_info = next_account_info(accounts_iter)?;
+        // Accrue and settle rewards before the balance changes
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        stake_pool.accrue(clock.unix_timestamp)?;
+        user_stake.settle(stake_pool)?;
 
-    // Verify reporter is signer
-    if !reporter_info.is_signer {
-        return Err(SolcatError::NotAuthorized.into());
+        // Update user stake and stake pool
+        user_stake.amount -= amount;
+        if user_stake.amount == 0 {
+            user_stake.lock_end_time = 0;
+        }
+        stake_pool.total_staked = stake_pool.total_staked
+            .checked_sub(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        let weight = calculate_stake_weight(user_stake.amount, user_stake.lock_end_time, clock.unix_timestamp);
+        user_stake.set_weight(stake_pool, weight);
+
+        // Tokens stay in the vault until the unbonding period has passed
+        queue_unbonding(
+            &mut ctx.accounts.unbonding,
+            ctx.accounts.staker.key(),
+            user_stake.key(),
+            amount,
+            0,
+            clock.unix_timestamp + ctx.accounts.config.unbonding_period,
+            clock.unix_timestamp,
+        )?;
+
+        msg!("Tokens unbonding: {}", amount);
+        Ok(())
     }
 
-    // Load existing report
-    let mut report = AddressReport::try_from_slice(&report_account_info.data.borrow())?;
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        let clock = Clock::get()?;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+
+        // Accrue and settle pending rewards, then let the lock boost decay
+        stake_pool.sync_treasury(
+            ctx.accounts.treasury.amount,
+            reserved_reporter_rewards(ctx.program_id, &ctx.accounts.reward_pool)?,
+        );
+        stake_pool.accrue(clock.unix_timestamp)?;
+        user_stake.settle(stake_pool)?;
+        let weight = calculate_stake_weight(user_stake.amount, user_stake.lock_end_time, clock.unix_timestamp);
+        user_stake.set_weight(stake_pool, weight);
+        let total_rewards = user_stake.rewards_earned;
+
+        require!(total_rewards > 0, SolcatError::InvalidRewardCalculation);
+
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.claimer_token_account.to_account_info(),
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            total_rewards,
+        )?;
+
+        // Update user stake
+        record_payout(&mut ctx.accounts.config, total_rewards)?;
+        user_stake.rewards_earned = 0;
+        stake_pool.total_paid = stake_pool.total_paid
+            .checked_add(total_rewards)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+
+        emit!(RewardsClaimed {
+            owner: user_stake.owner,
+            source: user_stake.key(),
+            kind: RewardKind::Staking,
+            amount: total_rewards,
+            timestamp: clock.unix_timestamp,
+        });
 
-    // Verify reporter owns the report
-    if report.reporter != *reporter_info.key {
-        return Err(SolcatError::NotAuthorized.into());
+        msg!("Rewards claimed successfully: {}", total_rewards);
+        Ok(())
     }
 
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
+    pub fn distribute_rewards(ctx: Context<DistributeRewards>) -> Result<()> {
+        let clock = Clock::get()?;
+        let config = &ctx.accounts.config;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+
+        // Accrue under the old schedule up to now before applying the configured one,
+        // staking instructions accrue on their own so this is only needed for schedule changes
+        stake_pool.accrue(clock.unix_timestamp)?;
+        if stake_pool.emission_schedule != config.emission_schedule {
+            stake_pool.emission_schedule = config.emission_schedule.clone();
+            stake_pool.epoch = 0;
+            stake_pool.epoch_emitted = 0;
+        }
+        stake_pool.sync_treasury(
+            ctx.accounts.treasury.amount,
+            reserved_reporter_rewards(ctx.program_id, &ctx.accounts.reward_pool)?,
+        );
 
-    // Check time lock
-    if clock.unix_timestamp < report.time_lock_end {
-        return Err(SolcatError::TimeLockActive.into());
-    }
+        emit!(RewardsDistributed {
+            epoch: stake_pool.epoch,
+            reward_per_token: stake_pool.reward_per_token,
+            total_distributed: stake_pool.total_distributed,
+            emission_cap: stake_pool.emission_cap,
+            timestamp: clock.unix_timestamp,
+        });
 
-    // Parse update data
-    let risk_score = data[0];
-    if risk_score > 100 {
-        return Err(SolcatError::InvalidRiskScore.into());
+        msg!("Rewards distributed successfully");
+        Ok(())
     }
 
-    let description_len = data[1] as usize;
-    let description = String::from_utf8_lossy(&data[2..2+description_len]).to_string();
+    pub fn submit_batch_report(
+        ctx: Context<SubmitBatchReport>,
+        addresses: Vec<Pubkey>,
+        risk_scores: Vec<u8>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+
+        // Check reporter stats, a batch counts as one report towards the cooldown and window
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        require!(
+            reporter_stats.reputation_score >= MIN_REPUTATION_SCORE,
+            SolcatError::InsufficientReputation
+        );
+        check_report_limits(reporter_stats, clock.unix_timestamp)?;
 
-    // Update report
-    report.risk_score = risk_score;
-    report.description = description;
-    report.last_update_time = clock.unix_timestamp;
-    report.time_lock_end = clock.unix_timestamp + TIME_LOCK_DURATION;
+        // Every address carries exactly one score
+        require!(
+            !addresses.is_empty() && addresses.len() <= MAX_BATCH_SIZE && addresses.len() == risk_scores.len(),
+            SolcatError::InvalidBatchReport
+        );
+        require!(risk_scores.iter().all(|score| *score <= 100), SolcatError::InvalidRiskScore);
+
+        record_report_activity(reporter_stats, clock.unix_timestamp)?;
+        ctx.accounts.batch_report.set_inner(BatchReport {
+            reporter: ctx.accounts.reporter.key(),
+            addresses,
+            risk_scores,
+            timestamp: clock.unix_timestamp,
+            verification_status: VerificationStatus::Pending,
+        });
 
-    // Save updated report
-    report.serialize(&mut *report_account_info.data.borrow_mut())?;
+        let batch_report = &ctx.accounts.batch_report;
+        emit!(BatchSubmitted {
+            batch_report: batch_report.key(),
+            reporter: batch_report.reporter,
+            addresses: batch_report.addresses.clone(),
+            risk_scores: batch_report.risk_scores.clone(),
+            timestamp: clock.unix_timestamp,
+        });
 
-    // Update stats
-    let mut stats = AddressStats::try_from_slice(&stats_account_info.data.borrow())?;
-    stats.weighted_risk_score = stats.weighted_risk_score
-        .saturating_sub((report.risk_score as u32) * report.vote_weight)
-        .saturating_add((risk_score as u32) * report.vote_weight);
-    stats.last_update = clock.unix_timestamp;
+        msg!("Batch report submitted successfully");
+        Ok(())
+    }
 
-    stats.serialize(&mut *stats_account_info.data.borrow_mut())?;
+    pub fn verify_batch_report(ctx: Context<VerifyBatchReport>, is_verified: bool) -> Result<()> {
+        // Check verifier stats, nobody verifies their own batch
+        require_keys_neq!(
+            ctx.accounts.verifier.key(),
+            ctx.accounts.batch_report.reporter,
+            SolcatError::NotAuthorized
+        );
+        // Quick flags are cheap to open, so only stake on full reports and an
+        // established reputation qualify a verifier
+        let verifier_stats = &ctx.accounts.verifier_stats;
+        require!(
+            verifier_stats.report_stake() >= MIN_VERIFICATION_STAKE,
+            SolcatError::InsufficientStake
+        );
+        require!(
+            verifier_stats.reputation_score >= MIN_VERIFIER_REPUTATION,
+            SolcatError::InsufficientReputation
+        );
 
-    msg!("Report updated successfully");
-    Ok(())
-}
+        let batch_report = &mut ctx.accounts.batch_report;
+        require!(
+            batch_report.verification_status == VerificationStatus::Pending,
+            SolcatError::BatchVerificationPending
+        );
 
-fn stake_on_report(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let staker_info = next_account_info(accounts_iter)?;
-    let report_account_info = next_account_info(accounts_iter)?;
-    let stats_account_info = next_account_info(accounts_iter)?;
-    let reporter_stats_info = next_account_info(accounts_iter)?;
-    let system_program_info = next_account_info(accounts_iter)?;
-    let config_info = next_account_info(accounts_iter)?;
-
-    // Verify staker is signer
-    if !staker_info.is_signer {
-        return Err(SolcatError::NotAuthorized.into());
-    }
-
-    // Load global config
-    let config = GlobalConfig::try_from_slice(&config_info.data.borrow())?;
-
-    // Parse stake amount
-    let stake_amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
-    if stake_amount < config.min_stake_amount {
-        return Err(SolcatError::InsufficientStake.into());
-    }
-
-    // Load existing report and reporter stats
-    let mut report = AddressReport::try_from_slice(&report_account_info.data.borrow())?;
-    let mut reporter_stats = ReporterStats::try_from_slice(&reporter_stats_info.data.borrow())?;
-
-    // Transfer SOL from staker to program account
-    let ix = solana_program::system_instruction::transfer(
-        staker_info.key,
-        report_account_info.key,
-        stake_amount,
-    );
-
-    solana_program::program::invoke(
-        &ix,
-        &[
-            staker_info.clone(),
-            report_account_info.clone(),
-            system_program_info.clone(),
-        ],
-    )?;
+        batch_report.verification_status = if is_verified {
+            VerificationStatus::Verified
+        } else {
+            VerificationStatus::Rejected
+        };
+
+        // A verified batch earns the reporter points for each address by its risk score,
+        // when the reward pool is passed
+        let points = match ctx.accounts.reward_pool.as_mut() {
+            Some(reward_pool) if is_verified => {
+                let reporter_stats = &mut ctx.accounts.reporter_stats;
+                let points = batch_report.risk_scores
+                    .iter()
+                    .map(|score| calculate_report_points(*score, reporter_stats.total_stake))
+                    .sum();
+                reward_pool.add_points(reporter_stats, points)?;
+                points
+            }
+            _ => 0,
+        };
+
+        emit!(BatchVerified {
+            batch_report: batch_report.key(),
+            verifier: ctx.accounts.verifier.key(),
+            reporter: batch_report.reporter,
+            verified: is_verified,
+            points,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    // Update report stake amount
-    report.stake_amount += stake_amount;
-    report.vote_weight = calculate_vote_weight(&reporter_stats);
+        msg!("Batch report verification completed");
+        Ok(())
+    }
 
-    // Save updated report
-    report.serialize(&mut *report_account_info.data.borrow_mut())?;
+    pub fn blacklist_address(ctx: Context<ApplyListing>, reason: String, expiry: i64) -> Result<()> {
+        // Blacklisting is the `Block` tier of a listing
+        apply_listing_instruction(ctx, ListingStatus::Block, reason, expiry)
+    }
 
-    // Update stats
-    let mut stats = AddressStats::try_from_slice(&stats_account_info.data.borrow())?;
-    stats.total_stake += stake_amount;
-    stats.weighted_risk_score = stats.weighted_risk_score
-        .saturating_sub((report.risk_score as u32) * report.vote_weight)
-        .saturating_add((report.risk_score as u32) * calculate_vote_weight(&reporter_stats));
-    stats.serialize(&mut *stats_account_info.data.borrow_mut())?;
+    pub fn update_history(ctx: Context<UpdateHistory>) -> Result<()> {
+        let report = &ctx.accounts.report;
+        let history = &mut ctx.accounts.report_history;
+        if history.address == Pubkey::default() {
+            history.address = report.reported_address;
+        }
 
-    // Update reporter stats
-    reporter_stats.total_stake += stake_amount;
-    reporter_stats.serialize(&mut *reporter_stats_info.data.borrow_mut())?;
+        let clock = Clock::get()?;
 
-    msg!("Stake added successfully");
-    Ok(())
-}
+        // Anyone may record a report, but only once per change to it
+        require!(
+            !history.reports.iter().any(|entry| {
+                entry.reporter == report.reporter && entry.timestamp >= report.last_update_time
+            }),
+            SolcatError::HistoryUpdateFailed
+        );
 
-fn update_reporter_stats(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let authority_info = next_account_info(accounts_iter)?;
-    let reporter_stats_info = next_account_info(accounts_iter)?;
+        // Add report to history
+        history.push_report(HistoricalReport {
+            timestamp: clock.unix_timestamp,
+            risk_score: report.risk_score,
+            reporter: report.reporter,
+            description: report.description.clone(),
+        });
 
-    // Verify authority is signer and is program upgrade authority
-    if !authority_info.is_signer || authority_info.key != program_id {
-        return Err(SolcatError::NotAuthorized.into());
+        emit!(HistoryUpdated {
+            address: report.reported_address,
+            report: report.key(),
+            risk_score: report.risk_score,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("History updated successfully");
+        Ok(())
     }
 
-    let mut reporter_stats = ReporterStats::try_from_slice(&reporter_stats_info.data.borrow())?;
-    
-    // Update reputation score based on successful reports
-    let new_reputation_score = data[0];
-    if new_reputation_score > 100 {
-        return Err(SolcatError::InvalidReportData.into());
+    pub fn remove_from_blacklist(ctx: Context<RemoveFromBlacklist>, rationale: String) -> Result<()> {
+        // A rationale is required so every removal can be justified
+        require!(
+            !rationale.is_empty() && rationale.len() <= MAX_BLACKLIST_REASON_LEN,
+            SolcatError::InvalidBlacklistOperation
+        );
+
+        let clock = Clock::get()?;
+
+        // An address that was never listed cannot be removed
+        let history = &mut ctx.accounts.report_history;
+        require!(
+            history.listing_status != ListingStatus::None,
+            SolcatError::InvalidBlacklistOperation
+        );
+
+        // Lift the listing, the previous reason is preserved in the log
+        history.listing_status = ListingStatus::None;
+        history.blacklist_reason = String::new();
+        history.blacklist_timestamp = 0;
+        history.blacklist_expiry = 0;
+        history.push_listing_change(BlacklistRecord {
+            action: BlacklistAction::Unblacklist,
+            status: ListingStatus::None,
+            authority: ctx.accounts.authority.key(),
+            reason: rationale.clone(),
+            timestamp: clock.unix_timestamp,
+            expiry: 0,
+        });
+
+        emit!(AddressUnblacklisted {
+            address: history.address,
+            authority: ctx.accounts.authority.key(),
+            rationale,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Address removed from blacklist successfully");
+        Ok(())
     }
 
-    reporter_stats.reputation_score = new_reputation_score;
-    reporter_stats.successful_reports += 1;
+    pub fn evaluate_blacklist(ctx: Context<EvaluateBlacklist>) -> Result<()> {
+        let stats = &ctx.accounts.address_stats;
+        let clock = Clock::get()?;
 
-    reporter_stats.serialize(&mut *reporter_stats_info.data.borrow_mut())?;
+        // History for the address the stats belong to, paid by the cranker
+        let history = &mut ctx.accounts.report_history;
+        if history.address == Pubkey::default() {
+            history.address = stats.address;
+        }
 
-    msg!("Reporter stats updated successfully");
-    Ok(())
-}
+        // A manual removal stands until the address is reported again
+        if let Some(record) = history.blacklist_log.last() {
+            require!(
+                record.action != BlacklistAction::Unblacklist || stats.last_report_time > record.timestamp,
+                SolcatError::InvalidBlacklistOperation
+            );
+        }
 
-// Helper function to calculate vote weight based on reporter stats
-fn calculate_vote_weight(reporter_stats: &ReporterStats) -> u32 {
-    let base_weight = 100u32;
-    let reputation_multiplier = (reporter_stats.reputation_score as u32).max(1);
-    let stake_multiplier = ((reporter_stats.total_stake / 1_000_000_000) as u32).max(1); // Convert lamports to SOL
-    let success_rate = if reporter_stats.total_reports > 0 {
-        (reporter_stats.successful_reports as f32 / reporter_stats.total_reports as f32 * 100.0) as u32
-    } else {
-        0
-    };
+        let (status, threshold) = ctx.accounts.config.listing_thresholds.evaluate(stats)
+            .ok_or(SolcatError::BlacklistThresholdNotMet)?;
 
-    base_weight
-        .saturating_mul(reputation_multiplier)
-        .saturating_mul(stake_multiplier)
-        .saturating_mul(success_rate.max(1))
-}
+        // The crank only escalates, downgrades are a manual decision
+        require!(
+            status > history.effective_status(clock.unix_timestamp),
+            SolcatError::AddressAlreadyBlacklisted
+        );
 
-fn stake_tokens(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let staker_info = next_account_info(accounts_iter)?;
-    let stake_pool_info = next_account_info(accounts_iter)?;
-    let user_stake_info = next_account_info(accounts_iter)?;
-    let token_mint_info = next_account_info(accounts_iter)?;
-    let config_info = next_account_info(accounts_iter)?;
-    let clock_sysvar_info = next_account_info(accounts_iter)?;
+        // Record the rule that fired so the listing can be justified later
+        let reason = format!(
+            "auto {:?}: score {} >= {}, reporters {} >= {}, stake {} >= {}",
+            status,
+            stats.aggregate_score(),
+            threshold.min_aggregate_score,
+            stats.reporters.len(),
+            threshold.min_distinct_reporters,
+            stats.total_stake,
+            threshold.min_total_stake,
+        );
 
-    // Verify staker is signer
-    if !staker_info.is_signer {
-        return Err(SolcatError::NotAuthorized.into());
-    }
+        // Automatic listings are attributed to the program itself
+        apply_listing(history, status, *ctx.program_id, reason, 0, clock.unix_timestamp);
 
-    // Load config and verify staking is enabled
-    let config = GlobalConfig::try_from_slice(&config_info.data.borrow())?;
-    if !config.staking_enabled {
-        return Err(SolcatError::StakingDisabled.into());
+        msg!("Address listed by threshold evaluation: {:?}", status);
+        Ok(())
     }
 
-    // Verify token mint
-    if config.token_mint != *token_mint_info.key {
-        return Err(SolcatError::InvalidTokenMint.into());
+    pub fn update_config(ctx: Context<UpdateConfig>, config: GlobalConfig) -> Result<()> {
+        // Replace the whole config, clients read-modify-write to change one field
+        require!(config.listing_thresholds.is_valid(), SolcatError::InvalidListingThreshold);
+        ctx.accounts.config.set_inner(config);
+        emit_config_updated(&ctx.accounts.authority, &ctx.accounts.config, Clock::get()?.unix_timestamp);
+
+        msg!("Config updated successfully");
+        Ok(())
     }
 
-    // Parse stake amount and duration
-    let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
-    let duration = i64::from_le_bytes(data[8..16].try_into().unwrap());
+    pub fn set_listing_status(
+        ctx: Context<ApplyListing>,
+        status: ListingStatus,
+        reason: String,
+        expiry: i64,
+    ) -> Result<()> {
+        // Lifting a listing goes through remove_from_blacklist so it carries a rationale
+        require!(status != ListingStatus::None, SolcatError::InvalidBlacklistOperation);
 
-    if amount == 0 {
-        return Err(SolcatError::InvalidStakeAmount.into());
+        apply_listing_instruction(ctx, status, reason, expiry)
     }
 
-    if duration < MIN_STAKE_DURATION || duration > MAX_STAKE_DURATION {
-        return Err(SolcatError::InvalidStakeAmount.into());
+    pub fn check_address(ctx: Context<CheckAddress>) -> Result<AddressRiskSummary> {
+        let summary = load_risk_summary(
+            ctx.program_id,
+            ctx.accounts.address.key,
+            &ctx.accounts.address_stats,
+            &ctx.accounts.report_history,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        msg!("Address checked: {:?} score {}", summary.listing_status, summary.aggregate_score);
+        Ok(summary)
     }
 
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
-    
-    // Load or create user stake account
-    let mut user_stake = if let Ok(stake) = UserStake::try_from_slice(&user_stake_info.data.borrow()) {
-        stake
-    } else {
-        UserStake {
-            owner: *staker_info.key,
-            amount: 0,
-            rewards_earned: 0,
-            reward_per_token_paid: 0,
-            lock_end_time: 0,
-        }
-    };
+    pub fn assert_low_risk(ctx: Context<AssertLowRisk>, max_score: u8) -> Result<()> {
+        require!(max_score <= 100, SolcatError::InvalidRiskScore);
 
-    // Update stake pool
-    let mut stake_pool = StakePool::try_from_slice(&stake_pool_info.data.borrow())?;
-    
-    // Calculate rewards before updating stake
-    let pending_reward = calculate_pending_rewards(&user_stake, &stake_pool);
-    user_stake.rewards_earned = user_stake.rewards_earned.saturating_add(pending_reward);
-    
-    // Update user stake
-    user_stake.amount = user_stake.amount.saturating_add(amount);
-    user_stake.lock_end_time = clock.unix_timestamp + duration;
-    user_stake.reward_per_token_paid = stake_pool.reward_per_token;
-
-    // Update stake pool
-    stake_pool.total_staked = stake_pool.total_staked.saturating_add(amount);
-    stake_pool.last_update_time = clock.unix_timestamp;
-
-    // Save state
-    user_stake.serialize(&mut *user_stake_info.data.borrow_mut())?;
-    stake_pool.serialize(&mut *stake_pool_info.data.borrow_mut())?;
-
-    msg!("Tokens staked successfully");
-    Ok(())
-}
+        // Listing expiries of every destination are judged at the same time
+        let now = Clock::get()?.unix_timestamp;
 
-fn unstake_tokens(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let staker_info = next_account_info(accounts_iter)?;
-    let stake_pool_info = next_account_info(accounts_iter)?;
-    let user_stake_info = next_account_info(accounts_iter)?;
-    let clock_sysvar_info = next_account_info(accounts_iter)?;
+        // Accounts come in (address, stats PDA, history PDA) triples, one per destination
+        let triples = ctx.remaining_accounts.chunks_exact(3);
+        if !triples.remainder().is_empty() {
+            return Err(ProgramError::NotEnoughAccountKeys.into());
+        }
+
+        for accounts in triples {
+            let address_info = &accounts[0];
+            let summary = load_risk_summary(
+                ctx.program_id,
+                address_info.key,
+                &accounts[1],
+                &accounts[2],
+                now,
+            )?;
+
+            // Blocked and sanctioned addresses fail regardless of their score
+            if summary.aggregate_score > max_score || summary.listing_status >= ListingStatus::Block {
+                msg!(
+                    "Risk threshold exceeded for {}: {:?} score {}",
+                    address_info.key,
+                    summary.listing_status,
+                    summary.aggregate_score
+                );
+                return err!(SolcatError::RiskThresholdExceeded);
+            }
+        }
 
-    // Verify staker is signer
-    if !staker_info.is_signer {
-        return Err(SolcatError::NotAuthorized.into());
+        Ok(())
     }
 
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
-    
-    // Load user stake
-    let mut user_stake = UserStake::try_from_slice(&user_stake_info.data.borrow())?;
+    pub fn refresh_stake_weight(ctx: Context<RefreshStakeWeight>) -> Result<()> {
+        let clock = Clock::get()?;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+
+        // Rewards up to now are earned at the old weight, except what a boost earned after
+        // its lock ended, which goes to whoever refreshes it so stale boosts do not linger
+        stake_pool.accrue(clock.unix_timestamp)?;
+        let weight = calculate_stake_weight(user_stake.amount, user_stake.lock_end_time, clock.unix_timestamp);
+        let bounty = user_stake.expired_boost_rewards(stake_pool, weight, clock.unix_timestamp)?;
+        user_stake.settle(stake_pool)?;
+        user_stake.set_weight(stake_pool, weight);
+        user_stake.rewards_earned -= bounty;
+
+        let refresher_stake = &mut ctx.accounts.refresher_stake;
+        refresher_stake.rewards_earned = refresher_stake.rewards_earned
+            .checked_add(bounty)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+
+        emit!(StakeWeightRefreshed {
+            user_stake: user_stake.key(),
+            weight: user_stake.weight,
+            refresher: ctx.accounts.refresher.key(),
+            bounty,
+            timestamp: clock.unix_timestamp,
+        });
 
-    // Verify ownership
-    if user_stake.owner != *staker_info.key {
-        return Err(SolcatError::NotAuthorized.into());
+        msg!("Stake weight refreshed: {}", user_stake.weight);
+        Ok(())
     }
 
-    // Check lock period
-    if clock.unix_timestamp < user_stake.lock_end_time {
-        return Err(SolcatError::StakeLocked.into());
+    pub fn early_unstake(ctx: Context<EarlyUnstake>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let config = &ctx.accounts.config;
+        let user_stake = &mut ctx.accounts.user_stake;
+
+        require!(amount > 0 && amount <= user_stake.amount, SolcatError::InvalidStakeAmount);
+
+        let penalty = calculate_early_unstake_penalty(
+            amount,
+            config.early_unstake_penalty_bps,
+            user_stake.lock_end_time,
+            clock.unix_timestamp,
+        );
+
+        // Accrue and settle rewards before the balance changes
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        stake_pool.accrue(clock.unix_timestamp)?;
+        user_stake.settle(stake_pool)?;
+
+        // Update user stake and stake pool, the lock stays in place for what remains
+        user_stake.amount -= amount;
+        if user_stake.amount == 0 {
+            user_stake.lock_end_time = 0;
+        }
+        stake_pool.total_staked = stake_pool.total_staked
+            .checked_sub(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        let weight = calculate_stake_weight(user_stake.amount, user_stake.lock_end_time, clock.unix_timestamp);
+        user_stake.set_weight(stake_pool, weight);
+
+        // Credit the penalty to the stakers that remain, after the leaver's weight is gone
+        if config.early_unstake_penalty_destination == PenaltyDestination::Stakers {
+            stake_pool.distribute_penalty(penalty)?;
+        }
+
+        // The rest unbonds like a regular unstake so it stays slashable,
+        // the penalty funds the treasury either way
+        queue_unbonding(
+            &mut ctx.accounts.unbonding,
+            ctx.accounts.staker.key(),
+            user_stake.key(),
+            amount - penalty,
+            penalty,
+            clock.unix_timestamp + config.unbonding_period,
+            clock.unix_timestamp,
+        )?;
+        if penalty > 0 {
+            transfer_from_vault(
+                &ctx.accounts.token_program,
+                ctx.accounts.stake_vault.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.vault_authority.to_account_info(),
+                ctx.bumps.vault_authority,
+                penalty,
+            )?;
+            record_return(&mut ctx.accounts.config, penalty)?;
+        }
+
+        msg!("Tokens unstaked early: {} penalty {}", amount - penalty, penalty);
+        Ok(())
     }
 
-    // Load stake pool
-    let mut stake_pool = StakePool::try_from_slice(&stake_pool_info.data.borrow())?;
+    pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>) -> Result<()> {
+        let clock = Clock::get()?;
+        let unbonding = &mut ctx.accounts.unbonding;
 
-    // Calculate final rewards
-    let pending_reward = calculate_pending_rewards(&user_stake, &stake_pool);
-    user_stake.rewards_earned = user_stake.rewards_earned.saturating_add(pending_reward);
+        require!(clock.unix_timestamp >= unbonding.release_time, SolcatError::UnbondingActive);
 
-    // Update stake pool
-    stake_pool.total_staked = stake_pool.total_staked.saturating_sub(user_stake.amount);
-    stake_pool.last_update_time = clock.unix_timestamp;
+        let amount = unbonding.amount;
+        require!(amount > 0, SolcatError::InvalidStakeAmount);
 
-    // Clear user stake
-    let amount = user_stake.amount;
-    user_stake.amount = 0;
-    user_stake.lock_end_time = 0;
+        // Release tokens from the vault
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.stake_vault.to_account_info(),
+            ctx.accounts.staker_token_account.to_account_info(),
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            amount,
+        )?;
 
-    // Save state
-    user_stake.serialize(&mut *user_stake_info.data.borrow_mut())?;
-    stake_pool.serialize(&mut *stake_pool_info.data.borrow_mut())?;
+        unbonding.amount = 0;
 
-    msg!("Tokens unstaked successfully: {}", amount);
-    Ok(())
-}
+        emit!(UnbondedWithdrawn {
+            owner: unbonding.owner,
+            unbonding: unbonding.key(),
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
 
-fn claim_rewards(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    _data: &[u8],
-) -> ProgramResult {
-    let claimer_info = next_account_info(accounts_iter)?;
-    let stake_pool_info = next_account_info(accounts_iter)?;
-    let user_stake_info = next_account_info(accounts_iter)?;
-    let treasury_info = next_account_info(accounts_iter)?;
-    let config_info = next_account_info(accounts_iter)?;
+        msg!("Unbonded tokens withdrawn: {}", amount);
+        Ok(())
+    }
+
+    pub fn slash_unbonding(ctx: Context<SlashUnbonding>, amount: u64) -> Result<()> {
+        let unbonding = &mut ctx.accounts.unbonding;
+        require!(amount > 0 && amount <= unbonding.amount, SolcatError::InvalidStakeAmount);
+
+        // Slashed tokens go to the treasury
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.stake_vault.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            amount,
+        )?;
+        record_return(&mut ctx.accounts.config, amount)?;
+
+        unbonding.amount -= amount;
+        unbonding.slashed_amount = unbonding.slashed_amount
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+
+        emit!(UnbondingSlashed {
+            owner: unbonding.owner,
+            unbonding: unbonding.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    // Verify claimer is signer
-    if !claimer_info.is_signer {
-        return Err(SolcatError::NotAuthorized.into());
+        msg!("Unbonding slashed: {}", amount);
+        Ok(())
     }
 
-    // Load config and verify treasury
-    let config = GlobalConfig::try_from_slice(&config_info.data.borrow())?;
-    if config.treasury != *treasury_info.key {
-        return Err(SolcatError::TreasuryMismatch.into());
+    pub fn fund_reporter_rewards(ctx: Context<FundReporterRewards>, amount: u64) -> Result<()> {
+        // The budget for the epoch being opened is paid out of the treasury on claim
+        let clock = Clock::get()?;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+
+        // The treasury must cover it on top of everything it already owes stakers and reporters
+        let owed = ctx.accounts.stake_pool.owed()
+            .checked_add(reward_pool.outstanding())
+            .and_then(|owed| owed.checked_add(amount))
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        require!(ctx.accounts.treasury.amount >= owed, SolcatError::InsufficientTokenBalance);
+
+        reward_pool.start_next_epoch(amount, clock.unix_timestamp)?;
+
+        emit!(ReporterRewardsFunded {
+            epoch: reward_pool.epoch,
+            budget: reward_pool.epoch_budget,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Reporter reward epoch {} funded: {}", reward_pool.epoch, reward_pool.epoch_budget);
+        Ok(())
     }
 
-    // Load user stake
-    let mut user_stake = UserStake::try_from_slice(&user_stake_info.data.borrow())?;
+    pub fn claim_reporter_rewards(ctx: Context<ClaimReporterRewards>) -> Result<()> {
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+
+        // Points from the open epoch are paid once it closes
+        reward_pool.settle(reporter_stats)?;
+        let amount = reporter_stats.token_balance;
+        require!(amount > 0, SolcatError::InvalidRewardCalculation);
+
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.reporter_token_account.to_account_info(),
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            amount,
+        )?;
+
+        record_payout(&mut ctx.accounts.config, amount)?;
+        reporter_stats.token_balance = 0;
+        reporter_stats.rewards_claimed = reporter_stats.rewards_claimed
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        reward_pool.total_claimed = reward_pool.total_claimed
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+
+        emit!(RewardsClaimed {
+            owner: ctx.accounts.reporter.key(),
+            source: reporter_stats.key(),
+            kind: RewardKind::Reporter,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    // Verify ownership
-    if user_stake.owner != *claimer_info.key {
-        return Err(SolcatError::NotAuthorized.into());
+        msg!("Reporter rewards claimed successfully: {}", amount);
+        Ok(())
     }
 
-    // Load stake pool
-    let stake_pool = StakePool::try_from_slice(&stake_pool_info.data.borrow())?;
+    pub fn compound_rewards(ctx: Context<CompoundRewards>) -> Result<()> {
+        // Verify staking is enabled
+        require!(ctx.accounts.config.staking_enabled, SolcatError::StakingDisabled);
+
+        let clock = Clock::get()?;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let stake_pool = &mut ctx.accounts.stake_pool;
 
-    // Calculate pending rewards
-    let pending_reward = calculate_pending_rewards(&user_stake, &stake_pool);
-    let total_rewards = user_stake.rewards_earned.saturating_add(pending_reward);
+        // Accrue and settle pending rewards
+        stake_pool.sync_treasury(
+            ctx.accounts.treasury.amount,
+            reserved_reporter_rewards(ctx.program_id, &ctx.accounts.reward_pool)?,
+        );
+        stake_pool.accrue(clock.unix_timestamp)?;
+        user_stake.settle(stake_pool)?;
+        let amount = user_stake.rewards_earned;
+
+        require!(amount > 0, SolcatError::InvalidRewardCalculation);
+
+        // Rewards join the position under its current lock
+        user_stake.rewards_earned = 0;
+        user_stake.amount = user_stake.amount
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        stake_pool.total_staked = stake_pool.total_staked
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        stake_pool.total_paid = stake_pool.total_paid
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        let weight = calculate_stake_weight(user_stake.amount, user_stake.lock_end_time, clock.unix_timestamp);
+        user_stake.set_weight(stake_pool, weight);
+        record_payout(&mut ctx.accounts.config, amount)?;
+
+        // Move the rewards from the treasury into the vault
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.stake_vault.to_account_info(),
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            amount,
+        )?;
+
+        emit!(RewardsCompounded {
+            owner: user_stake.owner,
+            user_stake: user_stake.key(),
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
 
-    if total_rewards == 0 {
-        return Err(SolcatError::InvalidRewardCalculation.into());
+        msg!("Rewards compounded successfully: {}", amount);
+        Ok(())
     }
 
-    // Update user stake
-    user_stake.rewards_earned = 0;
-    user_stake.reward_per_token_paid = stake_pool.reward_per_token;
+    pub fn delegate_stake(ctx: Context<DelegateStake>, amount: u64) -> Result<()> {
+        // Verify staking is enabled
+        require!(ctx.accounts.config.staking_enabled, SolcatError::StakingDisabled);
+        require!(amount > 0, SolcatError::InvalidStakeAmount);
 
-    // Save state
-    user_stake.serialize(&mut *user_stake_info.data.borrow_mut())?;
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        let delegation = &mut ctx.accounts.delegation;
 
-    msg!("Rewards claimed successfully: {}", total_rewards);
-    Ok(())
-}
+        // A new delegation starts earning from the current reward index
+        if delegation.delegator == Pubkey::default() {
+            delegation.delegator = ctx.accounts.delegator.key();
+            delegation.reporter = ctx.accounts.reporter.key();
+            delegation.reward_per_share_paid = reporter_stats.delegation_reward_per_share;
+        }
 
-fn distribute_rewards(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let authority_info = next_account_info(accounts_iter)?;
-    let stake_pool_info = next_account_info(accounts_iter)?;
-    let config_info = next_account_info(accounts_iter)?;
-    let clock_sysvar_info = next_account_info(accounts_iter)?;
-
-    // Verify authority is signer and program upgrade authority
-    if !authority_info.is_signer || authority_info.key != program_id {
-        return Err(SolcatError::NotAuthorized.into());
-    }
-
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
-    
-    // Load config and stake pool
-    let config = GlobalConfig::try_from_slice(&config_info.data.borrow())?;
-    let mut stake_pool = StakePool::try_from_slice(&stake_pool_info.data.borrow())?;
-
-    // Calculate new rewards
-    let time_elapsed = clock.unix_timestamp - stake_pool.last_update_time;
-    if time_elapsed > 0 && stake_pool.total_staked > 0 {
-        let reward_amount = config.reward_rate.saturating_mul(time_elapsed as u64);
-        let reward_per_token = reward_amount.saturating_div(stake_pool.total_staked);
-        
-        stake_pool.reward_per_token = stake_pool.reward_per_token.saturating_add(reward_per_token);
-        stake_pool.last_update_time = clock.unix_timestamp;
+        // Settle rewards before the share count changes
+        delegation.settle(reporter_stats)?;
+
+        let shares = reporter_stats.delegation_shares_for(amount)?;
+        require!(shares > 0, SolcatError::InvalidStakeAmount);
+        delegation.shares = delegation.shares
+            .checked_add(shares)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        reporter_stats.delegation_shares = reporter_stats.delegation_shares
+            .checked_add(shares)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+        reporter_stats.delegated_stake = reporter_stats.delegated_stake
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+
+        // Move tokens into the vault
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.delegator_token_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.delegator.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(StakeDelegated {
+            delegator: ctx.accounts.delegator.key(),
+            reporter: ctx.accounts.reporter.key(),
+            delegation: ctx.accounts.delegation.key(),
+            amount,
+            shares,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        // Save state
-        stake_pool.serialize(&mut *stake_pool_info.data.borrow_mut())?;
+        msg!("Stake delegated successfully: {} for {} shares", amount, shares);
+        Ok(())
     }
 
-    msg!("Rewards distributed successfully");
-    Ok(())
-}
+    pub fn undelegate_stake(ctx: Context<UndelegateStake>, shares: u64) -> Result<()> {
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        let delegation = &mut ctx.accounts.delegation;
+
+        // Burned shares are valued after any slashing so far
+        require!(shares > 0 && shares <= delegation.shares, SolcatError::InvalidStakeAmount);
+        let amount = reporter_stats.delegation_value(shares);
+
+        delegation.settle(reporter_stats)?;
+        delegation.shares -= shares;
+        reporter_stats.delegation_shares -= shares;
+        reporter_stats.delegated_stake -= amount;
+
+        // Undelegated tokens stay slashable while they unbond
+        if amount > 0 {
+            let clock = Clock::get()?;
+            queue_unbonding(
+                &mut ctx.accounts.unbonding,
+                ctx.accounts.delegator.key(),
+                delegation.key(),
+                amount,
+                0,
+                clock.unix_timestamp + ctx.accounts.config.unbonding_period,
+                clock.unix_timestamp,
+            )?;
+        }
 
-// Helper function to calculate pending rewards
-fn calculate_pending_rewards(user_stake: &UserStake, stake_pool: &StakePool) -> u64 {
-    if user_stake.amount == 0 {
-        return 0;
+        emit!(StakeUndelegated {
+            delegator: delegation.delegator,
+            reporter: delegation.reporter,
+            delegation: delegation.key(),
+            amount,
+            shares,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Stake undelegated successfully: {}", amount);
+        Ok(())
     }
 
-    let reward_per_token_delta = stake_pool.reward_per_token.saturating_sub(user_stake.reward_per_token_paid);
-    user_stake.amount.saturating_mul(reward_per_token_delta).saturating_div(1_000_000_000)
-}
+    pub fn set_commission(ctx: Context<SetCommission>, commission_bps: u16) -> Result<()> {
+        // Commission is in basis points of the delegators' share
+        require!(commission_bps as u64 <= BASIS_POINTS, SolcatError::InvalidReportData);
 
-fn submit_batch_report(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let reporter_info = next_account_info(accounts_iter)?;
-    let batch_report_info = next_account_info(accounts_iter)?;
-    let reporter_stats_info = next_account_info(accounts_iter)?;
-    let clock_sysvar_info = next_account_info(accounts_iter)?;
+        // Applies to rewards settled from now on
+        ctx.accounts.reporter_stats.commission_bps = commission_bps;
+
+        emit!(CommissionSet {
+            reporter: ctx.accounts.reporter.key(),
+            commission_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    // Verify reporter is signer
-    if !reporter_info.is_signer {
-        return Err(SolcatError::NotAuthorized.into());
+        msg!("Commission set to {} bps", commission_bps);
+        Ok(())
     }
 
-    // Check reporter stats
-    let reporter_stats = ReporterStats::try_from_slice(&reporter_stats_info.data.borrow())?;
-    if reporter_stats.reputation_score < MIN_REPUTATION_SCORE {
-        return Err(SolcatError::InsufficientReputation.into());
+    pub fn claim_delegation_rewards(ctx: Context<ClaimDelegationRewards>) -> Result<()> {
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        let delegation = &mut ctx.accounts.delegation;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+
+        // Settle the reporter first so closed epochs reach the delegators
+        reward_pool.settle(reporter_stats)?;
+        delegation.settle(reporter_stats)?;
+        let amount = delegation.rewards_earned;
+        require!(amount > 0, SolcatError::InvalidRewardCalculation);
+
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.delegator_token_account.to_account_info(),
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            amount,
+        )?;
+
+        record_payout(&mut ctx.accounts.config, amount)?;
+        delegation.rewards_earned = 0;
+        reward_pool.total_claimed = reward_pool.total_claimed
+            .checked_add(amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
+
+        emit!(RewardsClaimed {
+            owner: delegation.delegator,
+            source: delegation.key(),
+            kind: RewardKind::Delegation,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Delegation rewards claimed successfully: {}", amount);
+        Ok(())
     }
 
-    // Parse batch data
-    let num_addresses = data[0] as usize;
-    if num_addresses == 0 || num_addresses > MAX_BATCH_SIZE {
-        return Err(SolcatError::InvalidBatchReport.into());
+    pub fn slash_delegation(ctx: Context<SlashDelegation>, amount: u64) -> Result<()> {
+        // The reporter's own delegation is created empty if they never delegated,
+        // their part of the slash is then nothing
+        let reporter = ctx.accounts.reporter.key();
+        let delegation = &mut ctx.accounts.reporter_delegation;
+        if delegation.delegator == Pubkey::default() {
+            delegation.delegator = reporter;
+            delegation.reporter = reporter;
+        }
+
+        // Settle first, the reporter's shares change below
+        let reporter_stats = &mut ctx.accounts.reporter_stats;
+        delegation.settle(reporter_stats)?;
+        let (reporter_part, delegators_part, reporter_shares) =
+            reporter_stats.slash_delegations(delegation.shares, amount)?;
+        delegation.shares = reporter_shares;
+
+        // Slashed delegations go to the treasury
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.stake_vault.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            amount,
+        )?;
+        record_return(&mut ctx.accounts.config, amount)?;
+
+        emit!(DelegationSlashed {
+            reporter: ctx.accounts.reporter_stats.key(),
+            reporter_part,
+            delegators_part,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Delegation slashed: {} reporter {} delegators", reporter_part, delegators_part);
+        Ok(())
     }
+}
 
-    let mut addresses = Vec::with_capacity(num_addresses);
-    let mut risk_scores = Vec::with_capacity(num_addresses);
+// Admin instructions are signed by the program keypair, as the upgrade authority
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut, address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    #[account(init, payer = authority, space = 8 + GLOBAL_CONFIG_SPACE, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(init, payer = authority, space = 8 + STAKE_POOL_SPACE, seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    pub system_program: Program<'info, System>,
+}
 
-    let mut offset = 1;
-    for _ in 0..num_addresses {
-        let pubkey_bytes: [u8; 32] = data[offset..offset+32].try_into().unwrap();
-        addresses.push(Pubkey::new_from_array(pubkey_bytes));
-        offset += 32;
+#[derive(Accounts)]
+pub struct ReportAddress<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+    /// CHECK: any address can be reported
+    pub reported_address: UncheckedAccount<'info>,
+    #[account(init, payer = reporter, space = 8 + ADDRESS_REPORT_SPACE)]
+    pub report: Account<'info, AddressReport>,
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = 8 + ADDRESS_STATS_SPACE,
+        seeds = [ADDRESS_STATS_SEED, reported_address.key().as_ref()],
+        bump
+    )]
+    pub address_stats: Account<'info, AddressStats>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    pub system_program: Program<'info, System>,
+}
 
-        let risk_score = data[offset];
-        if risk_score > 100 {
-            return Err(SolcatError::InvalidRiskScore.into());
-        }
-        risk_scores.push(risk_score);
-        offset += 1;
-    }
-
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
-    
-    let batch_report = BatchReport {
-        reporter: *reporter_info.key,
-        addresses,
-        risk_scores,
-        timestamp: clock.unix_timestamp,
-        verification_status: VerificationStatus::Pending,
-    };
+#[derive(Accounts)]
+pub struct MarkSuspiciousAddress<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+    /// CHECK: any address can be flagged
+    pub reported_address: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = 8 + ADDRESS_REPORT_SPACE,
+        seeds = [SUSPICIOUS_SEED, reported_address.key().as_ref(), reporter.key().as_ref()],
+        bump
+    )]
+    pub report: Account<'info, AddressReport>,
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = 8 + ADDRESS_STATS_SPACE,
+        seeds = [ADDRESS_STATS_SEED, reported_address.key().as_ref()],
+        bump
+    )]
+    pub address_stats: Account<'info, AddressStats>,
+    // Flagging needs no prior registration, the reporter pays for their stats account
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = 8 + REPORTER_STATS_SPACE,
+        seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()],
+        bump
+    )]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    pub system_program: Program<'info, System>,
+}
 
-    batch_report.serialize(&mut *batch_report_info.data.borrow_mut())?;
+#[derive(Accounts)]
+pub struct UnmarkSuspiciousAddress<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+    #[account(
+        mut,
+        close = reporter,
+        seeds = [SUSPICIOUS_SEED, report.reported_address.as_ref(), reporter.key().as_ref()],
+        bump
+    )]
+    pub report: Account<'info, AddressReport>,
+    #[account(mut, seeds = [ADDRESS_STATS_SEED, report.reported_address.as_ref()], bump)]
+    pub address_stats: Account<'info, AddressStats>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+}
 
-    msg!("Batch report submitted successfully");
-    Ok(())
+#[derive(Accounts)]
+pub struct UpdateReport<'info> {
+    pub reporter: Signer<'info>,
+    #[account(mut, has_one = reporter @ SolcatError::NotAuthorized)]
+    pub report: Account<'info, AddressReport>,
+    #[account(mut, seeds = [ADDRESS_STATS_SEED, report.reported_address.as_ref()], bump)]
+    pub address_stats: Account<'info, AddressStats>,
 }
 
-fn verify_batch_report(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let verifier_info = next_account_info(accounts_iter)?;
-    let batch_report_info = next_account_info(accounts_iter)?;
-    let verifier_stats_info = next_account_info(accounts_iter)?;
-    let system_program_info = next_account_info(accounts_iter)?;
+#[derive(Accounts)]
+pub struct StakeOnReport<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    #[account(mut)]
+    pub report: Account<'info, AddressReport>,
+    #[account(mut, seeds = [ADDRESS_STATS_SEED, report.reported_address.as_ref()], bump)]
+    pub address_stats: Account<'info, AddressStats>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, report.reporter.as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateReporterStats<'info> {
+    #[account(mut, address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    /// CHECK: the reporter being rated, registered on first update
+    pub reporter: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + REPORTER_STATS_SPACE,
+        seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()],
+        bump
+    )]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    /// A verified report to credit, passed together with the reward pool
+    #[account(mut)]
+    pub report: Option<Account<'info, AddressReport>>,
+    #[account(mut, seeds = [REPORTER_REWARD_POOL_SEED], bump)]
+    pub reward_pool: Option<Account<'info, ReporterRewardPool>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, duration: i64, position: u32)]
+pub struct StakeTokens<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    #[account(mut, seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + USER_STAKE_SPACE,
+        seeds = [USER_STAKE_SEED, staker.key().as_ref(), &position.to_le_bytes()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = config.stake_vault @ SolcatError::InvalidTokenMint)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeTokens<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    #[account(mut, seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(mut, constraint = user_stake.owner == staker.key() @ SolcatError::NotAuthorized)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + UNBONDING_SPACE,
+        seeds = [UNBONDING_SEED, user_stake.key().as_ref()],
+        bump
+    )]
+    pub unbonding: Account<'info, Unbonding>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    pub claimer: Signer<'info>,
+    #[account(mut, seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(mut, constraint = user_stake.owner == claimer.key() @ SolcatError::NotAuthorized)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut, address = config.treasury @ SolcatError::TreasuryMismatch)]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub claimer_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA that owns the treasury and stake vault
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: read by reserved_reporter_rewards, may not exist yet
+    #[account(seeds = [REPORTER_REWARD_POOL_SEED], bump)]
+    pub reward_pool: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeRewards<'info> {
+    #[account(address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(address = config.treasury @ SolcatError::TreasuryMismatch)]
+    pub treasury: Account<'info, TokenAccount>,
+    /// CHECK: read by reserved_reporter_rewards, may not exist yet
+    #[account(seeds = [REPORTER_REWARD_POOL_SEED], bump)]
+    pub reward_pool: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitBatchReport<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+    #[account(init, payer = reporter, space = 8 + BATCH_REPORT_SPACE)]
+    pub batch_report: Account<'info, BatchReport>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyBatchReport<'info> {
+    pub verifier: Signer<'info>,
+    #[account(mut)]
+    pub batch_report: Account<'info, BatchReport>,
+    #[account(seeds = [REPORTER_STATS_SEED, verifier.key().as_ref()], bump)]
+    pub verifier_stats: Account<'info, ReporterStats>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, batch_report.reporter.as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    #[account(mut, seeds = [REPORTER_REWARD_POOL_SEED], bump)]
+    pub reward_pool: Option<Account<'info, ReporterRewardPool>>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyListing<'info> {
+    #[account(mut, address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    /// CHECK: the address being listed
+    pub address: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + REPORT_HISTORY_SPACE,
+        seeds = [REPORT_HISTORY_SEED, address.key().as_ref()],
+        bump
+    )]
+    pub report_history: Account<'info, ReportHistory>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateHistory<'info> {
+    pub report: Account<'info, AddressReport>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + REPORT_HISTORY_SPACE,
+        seeds = [REPORT_HISTORY_SEED, report.reported_address.as_ref()],
+        bump
+    )]
+    pub report_history: Account<'info, ReportHistory>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromBlacklist<'info> {
+    #[account(address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub report_history: Account<'info, ReportHistory>,
+}
+
+#[derive(Accounts)]
+pub struct EvaluateBlacklist<'info> {
+    #[account(seeds = [ADDRESS_STATS_SEED, address_stats.address.as_ref()], bump)]
+    pub address_stats: Account<'info, AddressStats>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + REPORT_HISTORY_SPACE,
+        seeds = [REPORT_HISTORY_SEED, address_stats.address.as_ref()],
+        bump
+    )]
+    pub report_history: Account<'info, ReportHistory>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CheckAddress<'info> {
+    /// CHECK: the address being checked
+    pub address: UncheckedAccount<'info>,
+    /// CHECK: verified and read by load_risk_summary, may not exist yet
+    pub address_stats: UncheckedAccount<'info>,
+    /// CHECK: verified and read by load_risk_summary, may not exist yet
+    pub report_history: UncheckedAccount<'info>,
+}
+
+// Addresses are passed as remaining accounts, see assert_low_risk
+#[derive(Accounts)]
+pub struct AssertLowRisk {}
+
+#[derive(Accounts)]
+pub struct RefreshStakeWeight<'info> {
+    // Anyone may decay a stale boost, the account types only admit state written by this program
+    pub refresher: Signer<'info>,
+    #[account(mut, seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(mut)]
+    pub user_stake: Account<'info, UserStake>,
+    // Credited with the bounty, claimed like any staking reward
+    #[account(
+        mut,
+        constraint = refresher_stake.owner == refresher.key() @ SolcatError::NotAuthorized,
+        constraint = refresher_stake.key() != user_stake.key() @ SolcatError::InvalidStakeAmount,
+    )]
+    pub refresher_stake: Account<'info, UserStake>,
+}
 
-    // Verify verifier is signer
-    if !verifier_info.is_signer {
-        return Err(SolcatError::NotAuthorized.into());
+#[derive(Accounts)]
+pub struct EarlyUnstake<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    #[account(mut, seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(mut, constraint = user_stake.owner == staker.key() @ SolcatError::NotAuthorized)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut, address = config.stake_vault @ SolcatError::InvalidTokenMint)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + UNBONDING_SPACE,
+        seeds = [UNBONDING_SEED, user_stake.key().as_ref()],
+        bump
+    )]
+    pub unbonding: Account<'info, Unbonding>,
+    #[account(mut, address = config.treasury @ SolcatError::TreasuryMismatch)]
+    pub treasury: Account<'info, TokenAccount>,
+    /// CHECK: PDA that owns the treasury and stake vault
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnbonded<'info> {
+    pub staker: Signer<'info>,
+    #[account(mut, constraint = unbonding.owner == staker.key() @ SolcatError::NotAuthorized)]
+    pub unbonding: Account<'info, Unbonding>,
+    #[account(mut, address = config.stake_vault @ SolcatError::InvalidTokenMint)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA that owns the treasury and stake vault
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SlashUnbonding<'info> {
+    #[account(address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub unbonding: Account<'info, Unbonding>,
+    #[account(mut, address = config.stake_vault @ SolcatError::InvalidTokenMint)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = config.treasury @ SolcatError::TreasuryMismatch)]
+    pub treasury: Account<'info, TokenAccount>,
+    /// CHECK: PDA that owns the treasury and stake vault
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FundReporterRewards<'info> {
+    #[account(mut, address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + REPORTER_REWARD_POOL_SPACE,
+        seeds = [REPORTER_REWARD_POOL_SEED],
+        bump
+    )]
+    pub reward_pool: Account<'info, ReporterRewardPool>,
+    #[account(seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(address = config.treasury @ SolcatError::TreasuryMismatch)]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReporterRewards<'info> {
+    pub reporter: Signer<'info>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    #[account(mut, seeds = [REPORTER_REWARD_POOL_SEED], bump)]
+    pub reward_pool: Account<'info, ReporterRewardPool>,
+    #[account(mut, address = config.treasury @ SolcatError::TreasuryMismatch)]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub reporter_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA that owns the treasury and stake vault
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundRewards<'info> {
+    pub staker: Signer<'info>,
+    #[account(mut, seeds = [STAKE_POOL_SEED], bump)]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(mut, constraint = user_stake.owner == staker.key() @ SolcatError::NotAuthorized)]
+    pub user_stake: Account<'info, UserStake>,
+    // Rewards can only be restaked when paid in the stake token
+    #[account(
+        mut,
+        address = config.treasury @ SolcatError::TreasuryMismatch,
+        constraint = treasury.mint == config.token_mint @ SolcatError::InvalidTokenMint
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(mut, address = config.stake_vault @ SolcatError::InvalidTokenMint)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA that owns the treasury and stake vault
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: read by reserved_reporter_rewards, may not exist yet
+    #[account(seeds = [REPORTER_REWARD_POOL_SEED], bump)]
+    pub reward_pool: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DelegateStake<'info> {
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+    /// CHECK: the reporter being backed, must already be registered
+    pub reporter: UncheckedAccount<'info>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        space = 8 + DELEGATION_SPACE,
+        seeds = [DELEGATION_SEED, reporter.key().as_ref(), delegator.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    #[account(mut)]
+    pub delegator_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = config.stake_vault @ SolcatError::InvalidTokenMint)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UndelegateStake<'info> {
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+    #[account(mut, has_one = delegator @ SolcatError::NotAuthorized)]
+    pub delegation: Account<'info, Delegation>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, delegation.reporter.as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        space = 8 + UNBONDING_SPACE,
+        seeds = [UNBONDING_SEED, delegation.key().as_ref()],
+        bump
+    )]
+    pub unbonding: Account<'info, Unbonding>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCommission<'info> {
+    pub reporter: Signer<'info>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDelegationRewards<'info> {
+    pub delegator: Signer<'info>,
+    #[account(mut, has_one = delegator @ SolcatError::NotAuthorized)]
+    pub delegation: Account<'info, Delegation>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, delegation.reporter.as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    #[account(mut, seeds = [REPORTER_REWARD_POOL_SEED], bump)]
+    pub reward_pool: Account<'info, ReporterRewardPool>,
+    #[account(mut, address = config.treasury @ SolcatError::TreasuryMismatch)]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub delegator_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA that owns the treasury and stake vault
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SlashDelegation<'info> {
+    #[account(mut, address = crate::ID @ SolcatError::NotAuthorized)]
+    pub authority: Signer<'info>,
+    /// CHECK: the reporter whose delegations are slashed
+    pub reporter: UncheckedAccount<'info>,
+    #[account(mut, seeds = [REPORTER_STATS_SEED, reporter.key().as_ref()], bump)]
+    pub reporter_stats: Account<'info, ReporterStats>,
+    /// The reporter's delegation to themselves, their principal
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + DELEGATION_SPACE,
+        seeds = [DELEGATION_SEED, reporter.key().as_ref(), reporter.key().as_ref()],
+        bump
+    )]
+    pub reporter_delegation: Account<'info, Delegation>,
+    #[account(mut, address = config.stake_vault @ SolcatError::InvalidTokenMint)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = config.treasury @ SolcatError::TreasuryMismatch)]
+    pub treasury: Account<'info, TokenAccount>,
+    /// CHECK: PDA that owns the treasury and stake vault
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+fn emit_config_updated(authority: &Signer, config: &GlobalConfig, now: i64) {
+    emit!(ConfigUpdated {
+        authority: authority.key(),
+        token_mint: config.token_mint,
+        treasury: config.treasury,
+        stake_vault: config.stake_vault,
+        staking_enabled: config.staking_enabled,
+        min_stake_amount: config.min_stake_amount,
+        timestamp: now,
+    });
+}
+
+fn emit_address_reported(report: &Account<AddressReport>, quick_flag: bool) {
+    emit!(AddressReported {
+        report: report.key(),
+        reporter: report.reporter,
+        reported_address: report.reported_address,
+        risk_score: report.risk_score,
+        base_score: report.risk_assessment.base_score,
+        confidence_score: report.risk_assessment.confidence_score,
+        vote_weight: report.vote_weight,
+        stake_amount: report.stake_amount,
+        quick_flag,
+        timestamp: report.timestamp,
+    });
+}
+
+// Helper function to enforce the cooldown and the report limit in the current window
+fn check_report_limits(reporter_stats: &mut ReporterStats, now: i64) -> Result<()> {
+    // Check cooldown period
+    require!(now >= reporter_stats.cooldown_end_time, SolcatError::CooldownActive);
+
+    // Check report limit in time window
+    if now - reporter_stats.last_report_time < REPORT_WINDOW_DURATION {
+        require!(
+            reporter_stats.reports_in_window < MAX_REPORTS_PER_WINDOW,
+            SolcatError::ReportLimitExceeded
+        );
+    } else {
+        // Reset window if it has expired
+        reporter_stats.reports_in_window = 0;
     }
 
-    // Check verifier stats
-    let verifier_stats = ReporterStats::try_from_slice(&verifier_stats_info.data.borrow())?;
-    if verifier_stats.total_stake < MIN_VERIFICATION_STAKE {
-        return Err(SolcatError::InsufficientStake.into());
+    Ok(())
+}
+
+// Helper function to add a new report to its address stats and the reporter's activity.
+// Stats are weighted by the reporter's base score, not the metrics-adjusted one.
+// Quick flags add no distinct reporter or stake, so they never help meet a listing threshold.
+fn record_report(
+    report: &AddressReport,
+    stats: &mut AddressStats,
+    reporter_stats: &mut ReporterStats,
+    quick_flag: bool,
+    now: i64,
+) -> Result<()> {
+    if stats.address == Pubkey::default() {
+        stats.address = report.reported_address;
     }
 
-    // Load batch report
-    let mut batch_report = BatchReport::try_from_slice(&batch_report_info.data.borrow())?;
-    if batch_report.verification_status != VerificationStatus::Pending {
-        return Err(SolcatError::BatchVerificationPending.into());
+    let risk_score = report.risk_assessment.base_score;
+    stats.total_reports = stats.total_reports
+        .checked_add(1)
+        .ok_or(SolcatError::ArithmeticOverflow)?;
+    if !quick_flag {
+        stats.add_reporter(report.reporter);
+        stats.total_stake = stats.total_stake
+            .checked_add(report.stake_amount)
+            .ok_or(SolcatError::ArithmeticOverflow)?;
     }
+    stats.push_risk_score(risk_score, report.timestamp);
+    stats.last_update = now;
+    stats.last_report_time = now;
+    stats.weighted_risk_score = stats.weighted_risk_score
+        .checked_add(risk_score as u128 * report.vote_weight as u128)
+        .ok_or(SolcatError::ArithmeticOverflow)?;
+    stats.total_vote_weight = stats.total_vote_weight
+        .checked_add(report.vote_weight)
+        .ok_or(SolcatError::ArithmeticOverflow)?;
+    stats.total_confidence = stats.total_confidence
+        .checked_add(report.risk_assessment.confidence_score as u64)
+        .ok_or(SolcatError::ArithmeticOverflow)?;
+
+    // Update reporter stats
+    reporter_stats.total_reports = reporter_stats.total_reports
+        .checked_add(1)
+        .ok_or(SolcatError::ArithmeticOverflow)?;
+    record_report_activity(reporter_stats, now)
+}
+
+// Helper function to start the reporter's cooldown and count a report in the current window
+fn record_report_activity(reporter_stats: &mut ReporterStats, now: i64) -> Result<()> {
+    reporter_stats.last_report_time = now;
+    reporter_stats.reports_in_window = reporter_stats.reports_in_window
+        .checked_add(1)
+        .ok_or(SolcatError::ArithmeticOverflow)?;
+    reporter_stats.cooldown_end_time = now + REPORT_COOLDOWN_PERIOD;
+    Ok(())
+}
+
+// Helper function to give a report a new base score. The stats weigh the base score,
+// the report's final score is recomputed from it like on submission.
+fn rescore_report(report: &mut AddressReport, stats: &mut AddressStats, risk_score: u8, now: i64) -> Result<()> {
+    stats.weighted_risk_score = replace_weighted_score(
+        stats.weighted_risk_score,
+        report.risk_assessment.base_score,
+        report.vote_weight,
+        risk_score,
+        report.vote_weight,
+    )?;
+    report.risk_assessment.base_score = risk_score;
+    report.risk_assessment.last_update = now;
+    report.risk_score = calculate_risk_score(&report.risk_assessment, &report.risk_metrics);
+    Ok(())
+}
+
+// Helper function to calculate reward points for a verified report.
+// Severity is scaled by the reporter's stake in whole SOL, capped so capital cannot dominate.
+fn calculate_report_points(severity: u8, total_stake: u64) -> u64 {
+    let stake_multiplier = 1 + (total_stake / 1_000_000_000).min(REPORT_POINTS_MAX_STAKE_MULTIPLIER);
+    severity as u64 * stake_multiplier
+}
 
-    // Parse verification decision
-    let is_verified = data[0] != 0;
-    batch_report.verification_status = if is_verified {
-        VerificationStatus::Verified
+// Helper function to calculate vote weight based on reporter stats.
+// Reputation is capped, stake counts by its square root so capital cannot dominate,
+// and the success rate scales the result between half and full weight.
+fn calculate_vote_weight(reporter_stats: &ReporterStats) -> Result<u64> {
+    let reputation_weight = (reporter_stats.reputation_score as u64).min(MAX_REPUTATION_WEIGHT);
+    let stake_weight = (reporter_stats.own_stake_weight() + reporter_stats.delegated_stake_weight()).min(MAX_STAKE_WEIGHT);
+    let success_rate = if reporter_stats.total_reports > 0 {
+        (reporter_stats.successful_reports as u64 * 100 / reporter_stats.total_reports as u64).min(100)
     } else {
-        VerificationStatus::Rejected
+        0
     };
 
-    batch_report.serialize(&mut *batch_report_info.data.borrow_mut())?;
+    let weight = (VOTE_WEIGHT_BASE + reputation_weight)
+        .checked_mul(1 + stake_weight)
+        .and_then(|w| w.checked_mul(100 + success_rate))
+        .ok_or(SolcatError::ArithmeticOverflow)?
+        / 200;
 
-    msg!("Batch report verification completed");
-    Ok(())
+    Ok(weight.max(1))
 }
 
-fn blacklist_address(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let authority_info = next_account_info(accounts_iter)?;
-    let address_history_info = next_account_info(accounts_iter)?;
-    let clock_sysvar_info = next_account_info(accounts_iter)?;
+// Helper function to swap one report's contribution to a weighted score sum
+fn replace_weighted_score(
+    weighted_risk_score: u128,
+    old_score: u8,
+    old_weight: u64,
+    new_score: u8,
+    new_weight: u64,
+) -> Result<u128> {
+    let score = weighted_risk_score
+        .checked_sub(old_score as u128 * old_weight as u128)
+        .and_then(|sum| sum.checked_add(new_score as u128 * new_weight as u128))
+        .ok_or(SolcatError::ArithmeticOverflow)?;
+    Ok(score)
+}
 
-    // Verify authority is signer and program upgrade authority
-    if !authority_info.is_signer || authority_info.key != program_id {
-        return Err(SolcatError::NotAuthorized.into());
+fn integer_sqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
     }
+    let mut x = value;
+    let mut y = x / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
 
-    // Parse blacklist data
-    let reason_len = data[0] as usize;
-    let reason = String::from_utf8_lossy(&data[1..1+reason_len]).to_string();
+// Helper function to add tokens to the unbonding record of a stake position.
+// Adding to a pending record restarts its unbonding period.
+fn queue_unbonding(
+    unbonding: &mut Account<Unbonding>,
+    owner: Pubkey,
+    user_stake: Pubkey,
+    amount: u64,
+    penalty: u64,
+    release_time: i64,
+    now: i64,
+) -> Result<()> {
+    // A new record is zeroed on creation
+    if unbonding.owner == Pubkey::default() {
+        unbonding.owner = owner;
+        unbonding.user_stake = user_stake;
+    }
+
+    unbonding.amount = unbonding.amount
+        .checked_add(amount)
+        .ok_or(SolcatError::ArithmeticOverflow)?;
+    unbonding.release_time = release_time;
+
+    emit!(UnbondingQueued {
+        owner,
+        source: user_stake,
+        unbonding: unbonding.key(),
+        amount,
+        penalty,
+        release_time,
+        timestamp: now,
+    });
+    Ok(())
+}
 
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
+// Helper function to calculate the early unstake penalty.
+// Like the weight boost it is proportional to the remaining lock, reaching zero at unlock.
+fn calculate_early_unstake_penalty(amount: u64, penalty_bps: u16, lock_end_time: i64, now: i64) -> u64 {
+    let remaining = lock_end_time.saturating_sub(now).clamp(0, MAX_STAKE_DURATION) as u128;
+    let penalty = amount as u128 * penalty_bps.min(BASIS_POINTS as u16) as u128 * remaining
+        / (BASIS_POINTS as u128 * MAX_STAKE_DURATION as u128);
 
-    // Load or create history
-    let mut history = if let Ok(h) = ReportHistory::try_from_slice(&address_history_info.data.borrow()) {
-        if h.is_blacklisted {
-            return Err(SolcatError::AddressAlreadyBlacklisted.into());
-        }
-        h
-    } else {
-        ReportHistory {
-            address: *address_history_info.key,
-            reports: Vec::new(),
-            is_blacklisted: false,
-            blacklist_reason: String::new(),
-            blacklist_timestamp: 0,
-        }
-    };
+    penalty as u64
+}
 
-    // Update blacklist status
-    history.is_blacklisted = true;
-    history.blacklist_reason = reason;
-    history.blacklist_timestamp = clock.unix_timestamp;
+// Helper function to move tokens out of a vault-authority-owned token account
+fn transfer_from_vault<'info>(
+    token_program: &Program<'info, Token>,
+    source: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    vault_authority: AccountInfo<'info>,
+    vault_authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Transfer {
+                from: source,
+                to: destination,
+                authority: vault_authority,
+            },
+            &[&[VAULT_AUTHORITY_SEED, &[vault_authority_bump]]],
+        ),
+        amount,
+    )
+}
 
-    history.serialize(&mut *address_history_info.data.borrow_mut())?;
+// Helper function to account for tokens leaving the treasury.
+// Payouts may never push circulating supply past the total supply.
+fn record_payout(config: &mut GlobalConfig, amount: u64) -> Result<()> {
+    config.circulating_supply = config.circulating_supply
+        .checked_add(amount)
+        .filter(|supply| *supply <= config.total_supply)
+        .ok_or(SolcatError::EmissionCapExceeded)?;
+    Ok(())
+}
 
-    msg!("Address blacklisted successfully");
+// Helper function to account for penalties and slashes moved back into the treasury
+fn record_return(config: &mut GlobalConfig, amount: u64) -> Result<()> {
+    config.circulating_supply = config.circulating_supply
+        .checked_sub(amount)
+        .ok_or(SolcatError::ArithmeticOverflow)?;
     Ok(())
 }
 
-fn update_history(
-    program_id: &Pubkey,
-    accounts_iter: &mut std::slice::Iter<AccountInfo>,
-    data: &[u8],
-) -> ProgramResult {
-    let report_info = next_account_info(accounts_iter)?;
-    let history_info = next_account_info(accounts_iter)?;
-    let clock_sysvar_info = next_account_info(accounts_iter)?;
-
-    // Load report and history
-    let report = AddressReport::try_from_slice(&report_info.data.borrow())?;
-    let mut history = if let Ok(h) = ReportHistory::try_from_slice(&history_info.data.borrow()) {
-        h
+// Helper function to calculate the lock-boosted weight of a stake.
+// The boost is proportional to the remaining lock and decays linearly to zero at unlock.
+fn calculate_stake_weight(amount: u64, lock_end_time: i64, now: i64) -> u64 {
+    let remaining = lock_end_time.saturating_sub(now).clamp(0, MAX_STAKE_DURATION) as u128;
+    let multiplier = REWARD_MULTIPLIER as u128
+        + STAKE_DURATION_MULTIPLIER as u128 * remaining / MAX_STAKE_DURATION as u128;
+
+    (amount as u128 * multiplier / REWARD_MULTIPLIER as u128).min(u64::MAX as u128) as u64
+}
+
+fn apply_listing_instruction(
+    ctx: Context<ApplyListing>,
+    status: ListingStatus,
+    reason: String,
+    expiry: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(reason.len() <= MAX_BLACKLIST_REASON_LEN, SolcatError::InvalidBlacklistOperation);
+
+    // A zero expiry means permanent, anything else must lie in the future
+    if expiry != 0 && expiry <= clock.unix_timestamp {
+        return err!(SolcatError::InvalidBlacklistOperation);
+    }
+
+    let history = &mut ctx.accounts.report_history;
+    if history.address == Pubkey::default() {
+        history.address = ctx.accounts.address.key();
+    }
+
+    apply_listing(history, status, ctx.accounts.authority.key(), reason, expiry, clock.unix_timestamp);
+
+    msg!("Address listed successfully: {:?}", status);
+    Ok(())
+}
+
+// Helper function to move a history to a new listing tier and log the transition
+fn apply_listing(
+    history: &mut ReportHistory,
+    status: ListingStatus,
+    authority: Pubkey,
+    reason: String,
+    expiry: i64,
+    now: i64,
+) {
+    // Re-listing at the current tier amends the reason and expiry in place
+    let action = if history.effective_status(now) == status {
+        BlacklistAction::Amend
     } else {
-        ReportHistory {
-            address: report.reported_address,
-            reports: Vec::new(),
-            is_blacklisted: false,
-            blacklist_reason: String::new(),
-            blacklist_timestamp: 0,
-        }
+        history.blacklist_timestamp = now;
+        BlacklistAction::Blacklist
     };
 
-    let clock = Clock::from_account_info(clock_sysvar_info)?;
+    history.listing_status = status;
+    history.blacklist_reason = reason.clone();
+    history.blacklist_expiry = expiry;
+    history.push_listing_change(BlacklistRecord {
+        action: action.clone(),
+        status,
+        authority,
+        reason: reason.clone(),
+        timestamp: now,
+        expiry,
+    });
 
-    // Add report to history
-    history.reports.push(HistoricalReport {
-        timestamp: clock.unix_timestamp,
-        risk_score: report.risk_score,
-        reporter: report.reporter,
-        description: report.description.clone(),
+    emit!(AddressBlacklisted {
+        address: history.address,
+        status,
+        action,
+        authority,
+        reason,
+        expiry,
+        timestamp: now,
     });
+}
 
-    history.serialize(&mut *history_info.data.borrow_mut())?;
+// Helper function to read the risk summary for an address from its PDAs.
+// Accounts that were never created read as an unlisted address with no reports.
+fn load_risk_summary(
+    program_id: &Pubkey,
+    address: &Pubkey,
+    stats_account_info: &AccountInfo,
+    address_history_info: &AccountInfo,
+    now: i64,
+) -> Result<AddressRiskSummary> {
+    // Verify the accounts are the PDAs of this address so callers cannot substitute them
+    if *stats_account_info.key != find_address_stats_address(program_id, address).0
+        || *address_history_info.key != find_report_history_address(program_id, address).0
+    {
+        return err!(SolcatError::InvalidAddress);
+    }
 
-    msg!("History updated successfully");
-    Ok(())
+    let stats = if stats_account_info.owner == program_id {
+        Some(AddressStats::try_deserialize(&mut &stats_account_info.try_borrow_data()?[..])?)
+    } else {
+        None
+    };
+    let history = if address_history_info.owner == program_id {
+        Some(ReportHistory::try_deserialize(&mut &address_history_info.try_borrow_data()?[..])?)
+    } else {
+        None
+    };
+
+    Ok(AddressRiskSummary::new(*address, stats.as_ref(), history.as_ref(), now))
+}
+
+// Helper function to read the reporter rewards the treasury still owes.
+// The reward pool only exists once reporter rewards are first funded.
+fn reserved_reporter_rewards(program_id: &Pubkey, reward_pool_info: &AccountInfo) -> Result<u64> {
+    if reward_pool_info.owner != program_id {
+        return Ok(0);
+    }
+    let reward_pool = ReporterRewardPool::try_deserialize(&mut &reward_pool_info.try_borrow_data()?[..])?;
+    Ok(reward_pool.outstanding())
 }
 
-// Helper function to calculate comprehensive risk score
-fn calculate_risk_score(risk_assessment: &RiskAssessment, risk_metrics: &RiskMetrics) -> u8 {
+// Helper function to calculate comprehensive risk score, public so off-chain
+// services score reports exactly as the program does
+pub fn calculate_risk_score(risk_assessment: &RiskAssessment, risk_metrics: &RiskMetrics) -> u8 {
     let base_score = risk_assessment.base_score as f32;
     let confidence_multiplier = risk_assessment.confidence_score as f32 / 100.0;
-    
+
     // Calculate metrics-based score
     let volume_score = if risk_metrics.transaction_volume > 1_000_000_000_000 { // 1000 SOL
         100.0
@@ -866,7 +2357,7 @@ fn calculate_risk_score(risk_assessment: &RiskAssessment, risk_metrics: &RiskMet
     let pattern_score = (risk_metrics.suspicious_patterns.len() as f32 / 5.0).min(1.0) * 100.0;
 
     // Calculate weighted score
-    let metrics_score = 
+    let metrics_score =
         volume_score * RISK_WEIGHT_TRANSACTION_VOLUME +
         interaction_score * RISK_WEIGHT_INTERACTIONS +
         age_score * RISK_WEIGHT_ACCOUNT_AGE +
@@ -889,4 +2380,96 @@ fn get_risk_type_severity(risk_type: &RiskType) -> u8 {
         RiskType::MarketManipulation => 70,
         RiskType::Unknown => 50,
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reporter_stats() -> ReporterStats {
+        ReporterStats {
+            total_reports: 0,
+            successful_reports: 0,
+            total_stake: 0,
+            quick_flag_stake: 0,
+            reputation_score: MIN_REPUTATION_SCORE,
+            last_report_time: 0,
+            reports_in_window: 0,
+            cooldown_end_time: 0,
+            token_balance: 0,
+            rewards_claimed: 0,
+            reward_points: 0,
+            points_epoch: 0,
+            delegated_stake: 0,
+            delegation_shares: 0,
+            commission_bps: 0,
+            delegation_reward_per_share: 0,
+        }
+    }
+
+    fn report(reported_address: Pubkey, base_score: u8, vote_weight: u64) -> AddressReport {
+        let risk_assessment = RiskAssessment {
+            base_score,
+            risk_types: vec![RiskType::Phishing],
+            confidence_score: 90,
+            evidence_count: 4,
+            last_update: 0,
+        };
+        let risk_metrics = RiskMetrics {
+            transaction_volume: 500_000_000_000,
+            unique_interactions: 1_000,
+            age_of_account: 0,
+            suspicious_patterns: vec!["drainer".to_string()],
+        };
+        AddressReport {
+            reporter: Pubkey::new_unique(),
+            reported_address,
+            risk_score: calculate_risk_score(&risk_assessment, &risk_metrics),
+            stake_amount: 0,
+            timestamp: 0,
+            description: String::new(),
+            vote_weight,
+            last_update_time: 0,
+            time_lock_end: 0,
+            risk_assessment,
+            risk_metrics,
+            verified: false,
+        }
+    }
+
+    #[test]
+    fn updates_reweigh_the_base_score_the_stats_hold() {
+        let address = Pubkey::new_unique();
+        let mut stats = AddressStats {
+            address,
+            total_reports: 0,
+            risk_scores: vec![],
+            risk_score_times: vec![],
+            total_stake: 0,
+            last_update: 0,
+            last_report_time: 0,
+            weighted_risk_score: 0,
+            total_vote_weight: 0,
+            reporters: vec![],
+            total_confidence: 0,
+        };
+        let mut reports = vec![report(address, 40, 30), report(address, 70, 55)];
+        for report in &reports {
+            record_report(report, &mut stats, &mut reporter_stats(), false, 0).unwrap();
+        }
+        // The metrics lift the final score above the base the stats weighed
+        assert!(reports[0].risk_score > reports[0].risk_assessment.base_score);
+
+        for (risk_score, now) in [(20, 10), (85, 20)] {
+            rescore_report(&mut reports[0], &mut stats, risk_score, now).unwrap();
+        }
+
+        let recomputed: u128 = reports
+            .iter()
+            .map(|report| report.risk_assessment.base_score as u128 * report.vote_weight as u128)
+            .sum();
+        assert_eq!(stats.weighted_risk_score, recomputed);
+        assert_eq!(reports[0].risk_assessment.base_score, 85);
+        assert_eq!(reports[0].risk_score, calculate_risk_score(&reports[0].risk_assessment, &reports[0].risk_metrics));
+    }
+}
@@ -1,7 +1,11 @@
-use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use anchor_lang::prelude::*;
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+use crate::error::SolcatError;
+
+// Fixed-point scale for StakePool::reward_per_token
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone, PartialEq)]
 pub enum RiskType {
     Scam,
     Phishing,
@@ -12,100 +16,943 @@ pub enum RiskType {
     Unknown,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone)]
 pub struct RiskAssessment {
     pub base_score: u8,
+    #[max_len(7)]
     pub risk_types: Vec<RiskType>,
     pub confidence_score: u8,
     pub evidence_count: u32,
     pub last_update: i64,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone)]
 pub struct RiskMetrics {
     pub transaction_volume: u64,
     pub unique_interactions: u32,
     pub age_of_account: i64,
+    #[max_len(10, 64)]
     pub suspicious_patterns: Vec<String>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+// Reports are sized for the largest assessment a reporter may submit,
+// these limits must match the `max_len` attributes on the report types
+pub const MAX_DESCRIPTION_LEN: usize = 255;
+pub const MAX_RISK_TYPES: usize = 7;
+pub const MAX_SUSPICIOUS_PATTERNS: usize = 10;
+pub const MAX_PATTERN_LEN: usize = 64;
+pub const ADDRESS_REPORT_SPACE: usize = AddressReport::INIT_SPACE;
+
+// Quick flags are reports at a PDA, so each reporter holds at most one flag per address
+pub const SUSPICIOUS_SEED: &[u8] = b"suspicious";
+
+pub fn find_suspicious_address(program_id: &Pubkey, address: &Pubkey, reporter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SUSPICIOUS_SEED, address.as_ref(), reporter.as_ref()], program_id)
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
 pub struct AddressReport {
     pub reporter: Pubkey,
     pub reported_address: Pubkey,
     pub risk_score: u8,
     pub stake_amount: u64,
     pub timestamp: i64,
+    #[max_len(255)]
     pub description: String,
-    pub vote_weight: u32,
+    pub vote_weight: u64,
     pub last_update_time: i64,
     pub time_lock_end: i64,
     pub risk_assessment: RiskAssessment,
     pub risk_metrics: RiskMetrics,
+    pub verified: bool,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+// AddressStats and ReportHistory live at PDAs derived from the reported address
+// so other programs can look them up without trusting the caller
+pub const ADDRESS_STATS_SEED: &[u8] = b"address_stats";
+pub const REPORT_HISTORY_SEED: &[u8] = b"report_history";
+// Both have a fixed size, so their lists are bounded and keep the latest entries.
+// Older entries remain in the program's events.
+pub const MAX_RECENT_RISK_SCORES: usize = 64;
+pub const MAX_TRACKED_REPORTERS: usize = 100;
+pub const MAX_HISTORY_REPORTS: usize = 16;
+pub const MAX_BLACKLIST_LOG: usize = 16;
+pub const MAX_BLACKLIST_REASON_LEN: usize = 128;
+pub const ADDRESS_STATS_SPACE: usize = 32 + 4 + (4 + MAX_RECENT_RISK_SCORES) + (4 + 8 * MAX_RECENT_RISK_SCORES) + 8 + 8 + 8 + 16 + 8
+    + (4 + 32 * MAX_TRACKED_REPORTERS) + 8;
+pub const REPORT_HISTORY_SPACE: usize = 32 + (4 + MAX_HISTORY_REPORTS * (8 + 1 + 32 + 4 + MAX_DESCRIPTION_LEN)) + 1
+    + (4 + MAX_BLACKLIST_REASON_LEN) + 8 + 8
+    + (4 + MAX_BLACKLIST_LOG * (1 + 1 + 32 + 4 + MAX_BLACKLIST_REASON_LEN + 8 + 8));
+
+pub fn find_address_stats_address(program_id: &Pubkey, address: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ADDRESS_STATS_SEED, address.as_ref()], program_id)
+}
+
+pub fn find_report_history_address(program_id: &Pubkey, address: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REPORT_HISTORY_SEED, address.as_ref()], program_id)
+}
+
+#[account]
+#[derive(Debug)]
 pub struct AddressStats {
+    pub address: Pubkey,
     pub total_reports: u32,
     pub risk_scores: Vec<u8>,
+    // Report time of each entry in risk_scores, so a withdrawn report removes its own score
+    pub risk_score_times: Vec<i64>,
     pub total_stake: u64,
     pub last_update: i64,
-    pub weighted_risk_score: u32,
-    pub total_vote_weight: u32,
+    // Time of the latest report, which updates, stakes and removals leave alone
+    pub last_report_time: i64,
+    pub weighted_risk_score: u128,
+    pub total_vote_weight: u64,
+    pub reporters: Vec<Pubkey>,
+    pub total_confidence: u64,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+impl AddressStats {
+    // Vote-weighted average of all risk scores reported for the address
+    pub fn aggregate_score(&self) -> u8 {
+        if self.total_vote_weight == 0 {
+            return 0;
+        }
+        (self.weighted_risk_score / self.total_vote_weight as u128).min(100) as u8
+    }
+
+    // Average confidence reporters attached to their assessments
+    pub fn confidence(&self) -> u8 {
+        if self.total_reports == 0 {
+            return 0;
+        }
+        (self.total_confidence / self.total_reports as u64).min(100) as u8
+    }
+
+    // Keep the latest scores, dropping the oldest once the list is full
+    pub fn push_risk_score(&mut self, score: u8, reported_at: i64) {
+        if self.risk_scores.len() >= MAX_RECENT_RISK_SCORES {
+            self.risk_scores.remove(0);
+            self.risk_score_times.remove(0);
+        }
+        self.risk_scores.push(score);
+        self.risk_score_times.push(reported_at);
+    }
+
+    // Drop the score a report pushed, unless it has already been evicted. Entries
+    // matching on both score and time are interchangeable, so any of them will do.
+    pub fn remove_risk_score(&mut self, score: u8, reported_at: i64) {
+        let index = self.risk_scores.iter()
+            .zip(&self.risk_score_times)
+            .position(|(entry, time)| *entry == score && *time == reported_at);
+        if let Some(index) = index {
+            self.risk_scores.remove(index);
+            self.risk_score_times.remove(index);
+        }
+    }
+
+    // Distinct reporters are counted up to MAX_TRACKED_REPORTERS, which no
+    // listing threshold may exceed, so a full list still meets every threshold
+    pub fn add_reporter(&mut self, reporter: Pubkey) {
+        if self.reporters.len() < MAX_TRACKED_REPORTERS && !self.reporters.contains(&reporter) {
+            self.reporters.push(reporter);
+        }
+    }
+}
+
+// Returned by CheckAddress as the instruction's return value
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq)]
+pub struct AddressRiskSummary {
+    pub address: Pubkey,
+    pub listing_status: ListingStatus,
+    pub aggregate_score: u8,
+    pub confidence: u8,
+    pub total_reports: u32,
+    pub distinct_reporters: u32,
+    pub total_stake: u64,
+}
+
+impl AddressRiskSummary {
+    // Summary of an address from its accounts, either of which may not exist yet
+    pub fn new(address: Pubkey, stats: Option<&AddressStats>, history: Option<&ReportHistory>, now: i64) -> Self {
+        let mut summary = AddressRiskSummary {
+            address,
+            listing_status: ListingStatus::None,
+            aggregate_score: 0,
+            confidence: 0,
+            total_reports: 0,
+            distinct_reporters: 0,
+            total_stake: 0,
+        };
+
+        if let Some(stats) = stats {
+            summary.aggregate_score = stats.aggregate_score();
+            summary.confidence = stats.confidence();
+            summary.total_reports = stats.total_reports;
+            summary.distinct_reporters = stats.reporters.len() as u32;
+            summary.total_stake = stats.total_stake;
+        }
+
+        if let Some(history) = history {
+            summary.listing_status = history.effective_status(now);
+        }
+
+        summary
+    }
+}
+
+// Own stake is held in lamports and delegations in stake tokens, which have 9 decimals.
+// They are never added up, each counts in whole SOL or whole tokens on its own.
+pub const LAMPORTS_PER_WHOLE_SOL: u64 = 1_000_000_000;
+pub const STAKE_TOKEN_UNIT: u64 = 1_000_000_000;
+
+pub const REPORTER_STATS_SEED: &[u8] = b"reporter_stats";
+pub const REPORTER_STATS_SPACE: usize = 4 + 4 + 8 + 8 + 1 + 8 + 4 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 2 + 16;
+
+pub fn find_reporter_stats_address(program_id: &Pubkey, reporter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REPORTER_STATS_SEED, reporter.as_ref()], program_id)
+}
+
+#[account]
+#[derive(Debug)]
 pub struct ReporterStats {
     pub total_reports: u32,
     pub successful_reports: u32,
     pub total_stake: u64,
+    // The part of `total_stake` held by quick flags
+    pub quick_flag_stake: u64,
     pub reputation_score: u8,
     pub last_report_time: i64,
     pub reports_in_window: u32,
     pub cooldown_end_time: i64,
+    // Settled reporter rewards that have not been claimed yet
     pub token_balance: u64,
     pub rewards_claimed: u64,
+    pub reward_points: u64,
+    pub points_epoch: u64,
+    // Tokens delegated by backers, owned pro rata through `delegation_shares`
+    pub delegated_stake: u64,
+    pub delegation_shares: u64,
+    pub commission_bps: u16,
+    pub delegation_reward_per_share: u128,
+}
+
+impl ReporterStats {
+    // Stake the reporter put behind full reports, quick flags back nothing but themselves
+    pub fn report_stake(&self) -> u64 {
+        self.total_stake.saturating_sub(self.quick_flag_stake)
+    }
+
+    // Square root of the whole SOL the reporter staked on reports
+    pub fn own_stake_weight(&self) -> u64 {
+        crate::integer_sqrt(self.total_stake / LAMPORTS_PER_WHOLE_SOL)
+    }
+
+    // Square root of the whole tokens delegated to the reporter, including their own delegation
+    pub fn delegated_stake_weight(&self) -> u64 {
+        crate::integer_sqrt(self.delegated_stake / STAKE_TOKEN_UNIT)
+    }
+
+    // Split earned rewards with delegators by their part of the stake weight, keeping commission on it
+    pub fn credit_rewards(&mut self, amount: u64) -> Result<()> {
+        let (own_weight, delegated_weight) = (self.own_stake_weight(), self.delegated_stake_weight());
+        let delegators_share = if self.delegation_shares > 0 && delegated_weight > 0 {
+            (amount as u128 * delegated_weight as u128 / (own_weight + delegated_weight) as u128) as u64
+        } else {
+            0
+        };
+        let commission = (delegators_share as u128 * self.commission_bps.min(10_000) as u128 / 10_000) as u64;
+        let to_delegators = delegators_share - commission;
+
+        if to_delegators > 0 {
+            let increment = (to_delegators as u128)
+                .checked_mul(REWARD_PRECISION)
+                .ok_or(SolcatError::InvalidRewardCalculation)?
+                / self.delegation_shares as u128;
+            self.delegation_reward_per_share = self.delegation_reward_per_share
+                .checked_add(increment)
+                .ok_or(SolcatError::InvalidRewardCalculation)?;
+        }
+
+        self.token_balance = self.token_balance
+            .checked_add(amount - to_delegators)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        Ok(())
+    }
+
+    // Token value of `shares` of the delegated stake, rounded down
+    pub fn delegation_value(&self, shares: u64) -> u64 {
+        if self.delegation_shares == 0 {
+            return 0;
+        }
+        (shares as u128 * self.delegated_stake as u128 / self.delegation_shares as u128) as u64
+    }
+
+    // Slash `amount` of the delegated stake in a single split. The reporter bears the
+    // commission share out of their own `reporter_shares`, as far as it goes, and the other
+    // delegators the rest, never more than they hold. The reporter's shares are then
+    // repriced at the delegators' new share price, so the delegators' part is not charged
+    // to the reporter again. Returns both parts and the reporter's new shares.
+    pub fn slash_delegations(&mut self, reporter_shares: u64, amount: u64) -> Result<(u64, u64, u64)> {
+        require!(amount > 0 && amount <= self.delegated_stake, SolcatError::InvalidStakeAmount);
+
+        let reporter_value = self.delegation_value(reporter_shares);
+        let others_value = self.delegated_stake - reporter_value;
+        let others_shares = self.delegation_shares
+            .checked_sub(reporter_shares)
+            .ok_or(SolcatError::InvalidStakeAmount)?;
+        let commission_part = (amount as u128 * self.commission_bps.min(10_000) as u128 / 10_000) as u64;
+        let delegators_part = (amount - commission_part.min(reporter_value)).min(others_value);
+        let reporter_part = amount - delegators_part;
+        let (reporter_left, others_left) = (reporter_value - reporter_part, others_value - delegators_part);
+
+        let reporter_shares_left = if others_shares == 0 || (others_left == 0 && reporter_left == 0) {
+            reporter_shares
+        } else {
+            // Shares cannot hold value for the reporter once the delegators' are worthless
+            require!(others_left > 0, SolcatError::InvalidStakeAmount);
+            u64::try_from(reporter_left as u128 * others_shares as u128 / others_left as u128)
+                .map_err(|_| error!(SolcatError::InvalidStakeAmount))?
+        };
+
+        self.delegated_stake -= amount;
+        self.delegation_shares = others_shares
+            .checked_add(reporter_shares_left)
+            .ok_or(SolcatError::InvalidStakeAmount)?;
+        Ok((reporter_part, delegators_part, reporter_shares_left))
+    }
+
+    // Shares worth `amount` tokens, rounded up so burning them covers the amount
+    pub fn delegation_shares_covering(&self, amount: u64) -> u64 {
+        if self.delegated_stake == 0 {
+            return self.delegation_shares;
+        }
+        let (value, stake) = (amount as u128 * self.delegation_shares as u128, self.delegated_stake as u128);
+        let shares = value / stake + u128::from(value % stake != 0);
+        shares.min(self.delegation_shares as u128) as u64
+    }
+
+    // Shares minted for delegating `amount` tokens, rounded down in favour of the pool.
+    // A pool slashed to nothing stays closed until its worthless shares are withdrawn.
+    pub fn delegation_shares_for(&self, amount: u64) -> Result<u64> {
+        if self.delegation_shares == 0 {
+            return Ok(amount);
+        }
+        if self.delegated_stake == 0 {
+            return err!(SolcatError::InvalidStakeAmount);
+        }
+        let shares = amount as u128 * self.delegation_shares as u128 / self.delegated_stake as u128;
+        u64::try_from(shares).map_err(|_| error!(SolcatError::InvalidRewardCalculation))
+    }
+}
+
+// One backer's delegation to one reporter
+pub const DELEGATION_SEED: &[u8] = b"delegation";
+// delegator + reporter + shares + reward_per_share_paid + rewards_earned
+pub const DELEGATION_SPACE: usize = 32 + 32 + 8 + 16 + 8;
+
+pub fn find_delegation_address(program_id: &Pubkey, reporter: &Pubkey, delegator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DELEGATION_SEED, reporter.as_ref(), delegator.as_ref()], program_id)
+}
+
+#[account]
+#[derive(Debug)]
+pub struct Delegation {
+    pub delegator: Pubkey,
+    pub reporter: Pubkey,
+    pub shares: u64,
+    pub reward_per_share_paid: u128,
+    pub rewards_earned: u64,
+}
+
+impl Delegation {
+    pub fn settle(&mut self, reporter_stats: &ReporterStats) -> Result<()> {
+        let delta = reporter_stats.delegation_reward_per_share.saturating_sub(self.reward_per_share_paid);
+        let pending = (self.shares as u128)
+            .checked_mul(delta)
+            .ok_or(SolcatError::InvalidRewardCalculation)?
+            / REWARD_PRECISION;
+        self.rewards_earned = self.rewards_earned
+            .checked_add(u64::try_from(pending).map_err(|_| SolcatError::InvalidRewardCalculation)?)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        self.reward_per_share_paid = reporter_stats.delegation_reward_per_share;
+        Ok(())
+    }
+}
+
+// Singleton pool paying reporters for verified reports. Each epoch has a budget
+// shared by the points earned during it, paid out once the next epoch is funded.
+pub const REPORTER_REWARD_POOL_SEED: &[u8] = b"reporter_reward_pool";
+// Points from older epochs than this are forfeited if never settled
+pub const MAX_CLOSED_REWARD_EPOCHS: usize = 26;
+pub const REPORTER_REWARD_POOL_SPACE: usize = 8 + 8 + 8 + 8
+    + 4 + MAX_CLOSED_REWARD_EPOCHS * (8 + 16 + 8) + 8 + 8;
+
+pub fn find_reporter_reward_pool_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REPORTER_REWARD_POOL_SEED], program_id)
+}
+
+#[account]
+#[derive(Debug)]
+pub struct ReporterRewardPool {
+    pub epoch: u64,
+    pub epoch_start: i64,
+    pub epoch_budget: u64,
+    pub epoch_points: u64,
+    pub closed_epochs: Vec<ClosedRewardEpoch>,
+    pub total_funded: u64,
+    pub total_claimed: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ClosedRewardEpoch {
+    pub epoch: u64,
+    pub reward_per_point: u128,
+    // Points earned in the epoch that reporters have not settled yet
+    pub unsettled_points: u64,
+}
+
+impl ClosedRewardEpoch {
+    pub fn reward_for(&self, points: u64) -> Result<u64> {
+        let reward = (points as u128)
+            .checked_mul(self.reward_per_point)
+            .ok_or(SolcatError::InvalidRewardCalculation)?
+            / REWARD_PRECISION;
+        u64::try_from(reward).map_err(|_| error!(SolcatError::InvalidRewardCalculation))
+    }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+impl ReporterRewardPool {
+    // Close the current epoch and open the next one with `funding` added to its budget.
+    // A budget nobody earned points against rolls over, and so do the rewards of
+    // points forfeited when their epoch is dropped.
+    pub fn start_next_epoch(&mut self, funding: u64, now: i64) -> Result<()> {
+        let mut carried_over = if self.epoch_points > 0 {
+            let reward_per_point = (self.epoch_budget as u128)
+                .checked_mul(REWARD_PRECISION)
+                .ok_or(SolcatError::InvalidRewardCalculation)?
+                / self.epoch_points as u128;
+            self.closed_epochs.push(ClosedRewardEpoch {
+                epoch: self.epoch,
+                reward_per_point,
+                unsettled_points: self.epoch_points,
+            });
+            0
+        } else {
+            self.epoch_budget
+        };
+        if self.closed_epochs.len() > MAX_CLOSED_REWARD_EPOCHS {
+            let dropped = self.closed_epochs.remove(0);
+            carried_over = carried_over
+                .checked_add(dropped.reward_for(dropped.unsettled_points)?)
+                .ok_or(SolcatError::InvalidRewardCalculation)?;
+        }
+
+        self.epoch += 1;
+        self.epoch_start = now;
+        self.epoch_budget = carried_over
+            .checked_add(funding)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        self.epoch_points = 0;
+        self.total_funded = self.total_funded
+            .checked_add(funding)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        Ok(())
+    }
+
+    // Convert points from a closed epoch into rewards for the reporter and their delegators
+    pub fn settle(&mut self, stats: &mut ReporterStats) -> Result<()> {
+        if stats.points_epoch == self.epoch {
+            return Ok(());
+        }
+
+        if let Some(closed) = self.closed_epochs.iter_mut().find(|e| e.epoch == stats.points_epoch) {
+            stats.credit_rewards(closed.reward_for(stats.reward_points)?)?;
+            closed.unsettled_points = closed.unsettled_points
+                .checked_sub(stats.reward_points)
+                .ok_or(SolcatError::InvalidRewardCalculation)?;
+        }
+
+        stats.reward_points = 0;
+        stats.points_epoch = self.epoch;
+        Ok(())
+    }
+
+    // Funded rewards not claimed yet, reserved in the treasury
+    pub fn outstanding(&self) -> u64 {
+        self.total_funded.saturating_sub(self.total_claimed)
+    }
+
+    pub fn add_points(&mut self, stats: &mut ReporterStats, points: u64) -> Result<()> {
+        self.settle(stats)?;
+        stats.reward_points = stats.reward_points
+            .checked_add(points)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        self.epoch_points = self.epoch_points
+            .checked_add(points)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        Ok(())
+    }
+}
+
+// Config and the stake pool are singletons created by `initialize`
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const STAKE_POOL_SEED: &[u8] = b"stake_pool";
+// Sized with every listing threshold set
+pub const GLOBAL_CONFIG_SPACE: usize = 8 + EMISSION_SCHEDULE_SPACE + 32 + 32 + 8 + 8 + 1 + 8
+    + 3 * (1 + 1 + 4 + 8) + 32 + 2 + 1 + 8;
+pub const STAKE_POOL_SPACE: usize = 8 + 8 + 16 + 8 + EMISSION_SCHEDULE_SPACE + 8 + 8 + 8 + 8 + 8;
+const EMISSION_SCHEDULE_SPACE: usize = 8 + 8 + 8 + 8 + 1 + 8;
+
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+pub fn find_stake_pool_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKE_POOL_SEED], program_id)
+}
+
+#[account]
+#[derive(Debug)]
 pub struct GlobalConfig {
     pub min_stake_amount: u64,
-    pub reward_rate: u64,
+    pub emission_schedule: EmissionSchedule,
     pub token_mint: Pubkey,
     pub treasury: Pubkey,
     pub total_supply: u64,
     pub circulating_supply: u64,
     pub staking_enabled: bool,
     pub min_lock_duration: i64,
+    pub listing_thresholds: ListingThresholds,
+    pub stake_vault: Pubkey,
+    pub early_unstake_penalty_bps: u16,
+    pub early_unstake_penalty_destination: PenaltyDestination,
+    pub unbonding_period: i64,
+}
+
+// Staking rewards are emitted in fixed-length epochs between `start_time` and `end_time`.
+// Each epoch's budget is spread evenly over the epoch and anything not accrued is never emitted.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Default, PartialEq)]
+pub struct EmissionSchedule {
+    pub start_time: i64,
+    pub end_time: i64,
+    pub epoch_duration: i64,
+    pub initial_epoch_emission: u64,
+    pub decay: EmissionDecay,
+    // Halving: epochs between halvings. Linear: amount removed from the budget each epoch.
+    pub decay_param: u64,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum EmissionDecay {
+    #[default]
+    Constant,
+    Halving,
+    Linear,
+}
+
+impl EmissionSchedule {
+    pub fn epoch_emission(&self, epoch: u64) -> u64 {
+        match self.decay {
+            EmissionDecay::Constant => self.initial_epoch_emission,
+            EmissionDecay::Halving => {
+                let halvings = epoch / self.decay_param.max(1);
+                self.initial_epoch_emission.checked_shr(halvings.min(u32::MAX as u64) as u32).unwrap_or(0)
+            }
+            EmissionDecay::Linear => self.initial_epoch_emission
+                .saturating_sub(self.decay_param.saturating_mul(epoch)),
+        }
+    }
+
+    // Sum of the budgets of epochs `from..to`, without visiting every epoch
+    pub fn emission_between(&self, from: u64, to: u64) -> u128 {
+        if from >= to {
+            return 0;
+        }
+
+        let initial = self.initial_epoch_emission as u128;
+        match self.decay {
+            EmissionDecay::Constant => initial * (to - from) as u128,
+            // One step per halving period, budgets are zero after 64 halvings
+            EmissionDecay::Halving => {
+                let period = self.decay_param.max(1);
+                let mut total = 0u128;
+                let mut epoch = from;
+                while epoch < to {
+                    let emission = self.epoch_emission(epoch);
+                    if emission == 0 {
+                        break;
+                    }
+                    let next = (epoch / period + 1).saturating_mul(period).min(to);
+                    total += emission as u128 * (next - epoch) as u128;
+                    epoch = next;
+                }
+                total
+            }
+            // Arithmetic series over the epochs whose budget is still positive
+            EmissionDecay::Linear => {
+                let to = match self.decay_param {
+                    0 => to,
+                    step => to.min(self.initial_epoch_emission.div_ceil(step)),
+                };
+                if from >= to {
+                    return 0;
+                }
+                let first = self.epoch_emission(from) as u128;
+                let last = self.epoch_emission(to - 1) as u128;
+                (first + last).saturating_mul((to - from) as u128) / 2
+            }
+        }
+    }
+}
+
+// Where early unstake penalties end up. Penalties are always moved to the treasury,
+// `Stakers` additionally earmarks them as rewards for the remaining stakers.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum PenaltyDestination {
+    Treasury,
+    Stakers,
+}
+
+// Token accounts for staked tokens and the treasury are owned by this PDA
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
+
+pub fn find_vault_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], program_id)
+}
+
+// Automatic listing rules per tier, `None` disables that tier for the crank.
+// `Sanctioned` is never assigned automatically.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Default)]
+pub struct ListingThresholds {
+    pub watch: Option<ListingThreshold>,
+    pub warn: Option<ListingThreshold>,
+    pub block: Option<ListingThreshold>,
+}
+
+impl ListingThresholds {
+    // Highest tier whose rule is met, together with the rule that fired
+    pub fn evaluate(&self, stats: &AddressStats) -> Option<(ListingStatus, &ListingThreshold)> {
+        [
+            (ListingStatus::Block, &self.block),
+            (ListingStatus::Warn, &self.warn),
+            (ListingStatus::Watch, &self.watch),
+        ]
+        .into_iter()
+        .find_map(|(status, threshold)| match threshold {
+            Some(t) if t.is_met_by(stats) => Some((status, t)),
+            _ => None,
+        })
+    }
+
+    // Stats count at most MAX_TRACKED_REPORTERS distinct reporters, a rule asking for more never fires
+    pub fn is_valid(&self) -> bool {
+        [&self.watch, &self.warn, &self.block]
+            .into_iter()
+            .flatten()
+            .all(|t| t.min_distinct_reporters as usize <= MAX_TRACKED_REPORTERS)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ListingThreshold {
+    pub min_aggregate_score: u8,
+    pub min_distinct_reporters: u32,
+    pub min_total_stake: u64,
+}
+
+impl ListingThreshold {
+    pub fn is_met_by(&self, stats: &AddressStats) -> bool {
+        stats.aggregate_score() >= self.min_aggregate_score
+            && stats.reporters.len() as u32 >= self.min_distinct_reporters
+            && stats.total_stake >= self.min_total_stake
+    }
+}
+
+#[account]
+#[derive(Debug)]
 pub struct StakePool {
     pub total_staked: u64,
-    pub reward_per_token: u64,
+    pub total_weight: u64,
+    pub reward_per_token: u128,
     pub last_update_time: i64,
-    pub reward_rate: u64,
+    pub emission_schedule: EmissionSchedule,
+    pub epoch: u64,
+    pub epoch_emitted: u64,
+    // Rewards credited to stakers and paid out of the treasury so far
+    pub total_distributed: u64,
+    pub total_paid: u64,
+    // Upper bound on `total_distributed`, refreshed from the treasury balance
+    pub emission_cap: u64,
+}
+
+impl StakePool {
+    // Accumulate rewards emitted by the schedule since the last update,
+    // shared by lock-boosted weight rather than raw stake.
+    // Nothing accrues while the pool is empty, so those rewards are never emitted.
+    // Accrual stops once the epoch budget or the treasury-backed cap is used up.
+    pub fn accrue(&mut self, now: i64) -> Result<()> {
+        if now <= self.last_update_time {
+            return Ok(());
+        }
+
+        let schedule = self.emission_schedule.clone();
+        let time = self.last_update_time.max(schedule.start_time);
+        let stop = now.min(schedule.end_time);
+
+        if self.total_weight > 0 && schedule.epoch_duration > 0 && time < stop {
+            let epoch_of = |time: i64| ((time - schedule.start_time) / schedule.epoch_duration) as u64;
+            let epoch_start = |epoch: u64| schedule.start_time
+                .saturating_add(schedule.epoch_duration.saturating_mul(epoch as i64));
+            let first = epoch_of(time);
+            let last = epoch_of(stop - 1);
+
+            if first == last {
+                self.accrue_within_epoch(first, time, stop)?;
+            } else {
+                // Epochs in between emit their whole budget, summed in closed form
+                self.accrue_within_epoch(first, time, epoch_start(first + 1))?;
+                let full = schedule.emission_between(first + 1, last);
+                let emitted = full.min(self.emission_cap.saturating_sub(self.total_distributed) as u128) as u64;
+                self.distribute(emitted)?;
+                self.accrue_within_epoch(last, epoch_start(last), stop)?;
+            }
+        }
+
+        self.last_update_time = now;
+        Ok(())
+    }
+
+    // Emit the share of `epoch`'s budget scheduled between `from` and `to`
+    fn accrue_within_epoch(&mut self, epoch: u64, from: i64, to: i64) -> Result<()> {
+        if epoch != self.epoch {
+            self.epoch = epoch;
+            self.epoch_emitted = 0;
+        }
+
+        let schedule = &self.emission_schedule;
+        let budget = schedule.epoch_emission(epoch);
+        let scheduled = (budget as u128 * (to - from) as u128 / schedule.epoch_duration as u128) as u64;
+        let emitted = scheduled
+            .min(budget.saturating_sub(self.epoch_emitted))
+            .min(self.emission_cap.saturating_sub(self.total_distributed));
+
+        self.epoch_emitted += emitted;
+        self.distribute(emitted)
+    }
+
+    // Share a one-off amount between current stakers by weight
+    pub fn distribute(&mut self, amount: u64) -> Result<()> {
+        if self.total_weight == 0 {
+            return Ok(());
+        }
+
+        let increment = (amount as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(SolcatError::InvalidRewardCalculation)?
+            / self.total_weight as u128;
+        self.reward_per_token = self.reward_per_token
+            .checked_add(increment)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        self.total_distributed = self.total_distributed
+            .checked_add(amount)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        Ok(())
+    }
+
+    // Share a penalty between current stakers. It is backed by tokens moved to the treasury,
+    // not by the emission budget, so the cap grows by what was credited. With no stakers
+    // left it stays in the treasury like a penalty that goes there.
+    pub fn distribute_penalty(&mut self, penalty: u64) -> Result<()> {
+        if self.total_weight == 0 {
+            return Ok(());
+        }
+        self.distribute(penalty)?;
+        self.emission_cap = self.emission_cap
+            .checked_add(penalty)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        Ok(())
+    }
+
+    // Emissions may only promise what the treasury can still pay,
+    // less the `reserved` reporter rewards it pays out of the same balance
+    pub fn sync_treasury(&mut self, treasury_balance: u64, reserved: u64) {
+        self.emission_cap = self.total_paid.saturating_add(treasury_balance.saturating_sub(reserved));
+    }
+
+    // Rewards credited to stakers that are still to be paid out of the treasury
+    pub fn owed(&self) -> u64 {
+        self.total_distributed.saturating_sub(self.total_paid)
+    }
+}
+
+// Each owner can hold several independent positions, keyed by index
+pub const USER_STAKE_SEED: &[u8] = b"user_stake";
+// owner + position + amount + weight + rewards_earned + reward_per_token_paid + lock_end_time + last_settle_time
+pub const USER_STAKE_SPACE: usize = 32 + 4 + 8 + 8 + 8 + 16 + 8 + 8;
+
+pub fn find_user_stake_address(program_id: &Pubkey, owner: &Pubkey, position: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[USER_STAKE_SEED, owner.as_ref(), &position.to_le_bytes()],
+        program_id,
+    )
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[account]
+#[derive(Debug)]
 pub struct UserStake {
     pub owner: Pubkey,
+    pub position: u32,
     pub amount: u64,
+    pub weight: u64,
     pub rewards_earned: u64,
-    pub reward_per_token_paid: u64,
+    pub reward_per_token_paid: u128,
     pub lock_end_time: i64,
+    // Time the pool had accrued to when rewards were last settled
+    pub last_settle_time: i64,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+impl UserStake {
+    pub fn pending_rewards(&self, stake_pool: &StakePool) -> Result<u64> {
+        let delta = stake_pool.reward_per_token.saturating_sub(self.reward_per_token_paid);
+        let pending = (self.weight as u128)
+            .checked_mul(delta)
+            .ok_or(SolcatError::InvalidRewardCalculation)?
+            / REWARD_PRECISION;
+        u64::try_from(pending).map_err(|_| error!(SolcatError::InvalidRewardCalculation))
+    }
+
+    // Move pending rewards into `rewards_earned`, must run before `weight` changes
+    pub fn settle(&mut self, stake_pool: &StakePool) -> Result<()> {
+        let pending = self.pending_rewards(stake_pool)?;
+        self.rewards_earned = self.rewards_earned
+            .checked_add(pending)
+            .ok_or(SolcatError::InvalidRewardCalculation)?;
+        self.reward_per_token_paid = stake_pool.reward_per_token;
+        self.last_settle_time = stake_pool.last_update_time;
+        Ok(())
+    }
+
+    // Pending rewards earned by the part of `weight` above `refreshed_weight` after the lock
+    // ended. Rewards are assumed to accrue evenly since the last settlement, must run before it.
+    pub fn expired_boost_rewards(&self, stake_pool: &StakePool, refreshed_weight: u64, now: i64) -> Result<u64> {
+        let stale_since = self.lock_end_time.max(self.last_settle_time);
+        if self.weight <= refreshed_weight || now <= stale_since {
+            return Ok(0);
+        }
+
+        let excess = self.pending_rewards(stake_pool)? as u128 * (self.weight - refreshed_weight) as u128
+            / self.weight as u128;
+        let stale = excess * (now - stale_since) as u128 / (now - self.last_settle_time) as u128;
+        u64::try_from(stale).map_err(|_| error!(SolcatError::InvalidRewardCalculation))
+    }
+
+    // Replace this stake's share of the pool weight, must run after `settle`
+    pub fn set_weight(&mut self, stake_pool: &mut StakePool, weight: u64) {
+        stake_pool.total_weight = stake_pool.total_weight
+            .saturating_sub(self.weight)
+            .saturating_add(weight);
+        self.weight = weight;
+    }
+}
+
+// Unstaked tokens wait here for `unbonding_period` and remain slashable meanwhile
+pub const UNBONDING_SEED: &[u8] = b"unbonding";
+// owner + user_stake + amount + release_time + slashed_amount
+pub const UNBONDING_SPACE: usize = 32 + 32 + 8 + 8 + 8;
+
+pub fn find_unbonding_address(program_id: &Pubkey, user_stake: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[UNBONDING_SEED, user_stake.as_ref()], program_id)
+}
+
+#[account]
+#[derive(Debug)]
+pub struct Unbonding {
+    pub owner: Pubkey,
+    pub user_stake: Pubkey,
+    pub amount: u64,
+    pub release_time: i64,
+    pub slashed_amount: u64,
+}
+
+#[account]
+#[derive(Debug)]
 pub struct ReportHistory {
     pub address: Pubkey,
     pub reports: Vec<HistoricalReport>,
-    pub is_blacklisted: bool,
+    pub listing_status: ListingStatus,
     pub blacklist_reason: String,
     pub blacklist_timestamp: i64,
+    pub blacklist_expiry: i64,
+    pub blacklist_log: Vec<BlacklistRecord>,
+}
+
+impl ReportHistory {
+    pub fn new(address: Pubkey) -> Self {
+        ReportHistory {
+            address,
+            reports: Vec::new(),
+            listing_status: ListingStatus::None,
+            blacklist_reason: String::new(),
+            blacklist_timestamp: 0,
+            blacklist_expiry: 0,
+            blacklist_log: Vec::new(),
+        }
+    }
+
+    // Keep the latest reports, dropping the oldest once the list is full
+    pub fn push_report(&mut self, report: HistoricalReport) {
+        if self.reports.len() >= MAX_HISTORY_REPORTS {
+            self.reports.remove(0);
+        }
+        self.reports.push(report);
+    }
+
+    // Keep the latest listing changes, dropping the oldest once the log is full
+    pub fn push_listing_change(&mut self, record: BlacklistRecord) {
+        if self.blacklist_log.len() >= MAX_BLACKLIST_LOG {
+            self.blacklist_log.remove(0);
+        }
+        self.blacklist_log.push(record);
+    }
+
+    // A listing with a zero expiry is permanent until explicitly removed
+    pub fn effective_status(&self, now: i64) -> ListingStatus {
+        if self.blacklist_expiry != 0 && now >= self.blacklist_expiry {
+            ListingStatus::None
+        } else {
+            self.listing_status
+        }
+    }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+// Ordered by severity, wallets warn from `Warn` and refuse to sign from `Block`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ListingStatus {
+    None,
+    Watch,
+    Warn,
+    Block,
+    Sanctioned,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq)]
+pub enum BlacklistAction {
+    Blacklist,
+    Amend,
+    Unblacklist,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct BlacklistRecord {
+    pub action: BlacklistAction,
+    pub status: ListingStatus,
+    pub authority: Pubkey,
+    pub reason: String,
+    pub timestamp: i64,
+    pub expiry: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
 pub struct HistoricalReport {
     pub timestamp: i64,
     pub risk_score: u8,
@@ -113,7 +960,11 @@ pub struct HistoricalReport {
     pub description: String,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub const MAX_BATCH_SIZE: usize = 10;
+pub const BATCH_REPORT_SPACE: usize = 32 + (4 + 32 * MAX_BATCH_SIZE) + (4 + MAX_BATCH_SIZE) + 8 + 1;
+
+#[account]
+#[derive(Debug)]
 pub struct BatchReport {
     pub reporter: Pubkey,
     pub addresses: Vec<Pubkey>,
@@ -122,9 +973,552 @@ pub struct BatchReport {
     pub verification_status: VerificationStatus,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq)]
 pub enum VerificationStatus {
     Pending,
     Verified,
     Rejected,
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Constant emission of `reward_rate` per second, backed by an unlimited treasury
+    fn pool(reward_rate: u64) -> StakePool {
+        scheduled_pool(EmissionSchedule {
+            start_time: 0,
+            end_time: i64::MAX,
+            epoch_duration: 86_400,
+            initial_epoch_emission: reward_rate * 86_400,
+            decay: EmissionDecay::Constant,
+            decay_param: 0,
+        })
+    }
+
+    fn scheduled_pool(emission_schedule: EmissionSchedule) -> StakePool {
+        StakePool {
+            total_staked: 0,
+            total_weight: 0,
+            reward_per_token: 0,
+            last_update_time: 0,
+            emission_schedule,
+            epoch: 0,
+            epoch_emitted: 0,
+            total_distributed: 0,
+            total_paid: 0,
+            emission_cap: u64::MAX,
+        }
+    }
+
+    fn stake() -> UserStake {
+        UserStake {
+            owner: Pubkey::new_unique(),
+            position: 0,
+            amount: 0,
+            weight: 0,
+            rewards_earned: 0,
+            reward_per_token_paid: 0,
+            lock_end_time: 0,
+            last_settle_time: 0,
+        }
+    }
+
+    // Mirrors stake_tokens: accrue, settle, then change the balance
+    fn deposit(pool: &mut StakePool, user: &mut UserStake, amount: u64, now: i64) {
+        pool.accrue(now).unwrap();
+        user.settle(pool).unwrap();
+        user.amount += amount;
+        pool.total_staked += amount;
+        user.set_weight(pool, user.amount);
+    }
+
+    fn withdraw_all(pool: &mut StakePool, user: &mut UserStake, now: i64) -> u64 {
+        pool.accrue(now).unwrap();
+        user.settle(pool).unwrap();
+        pool.total_staked -= user.amount;
+        user.amount = 0;
+        user.set_weight(pool, 0);
+        std::mem::take(&mut user.rewards_earned)
+    }
+
+    #[test]
+    fn small_staker_earns_rewards() {
+        let mut pool = pool(1_000);
+        let mut whale = stake();
+        let mut minnow = stake();
+
+        deposit(&mut pool, &mut whale, 1_000_000_000_000, 0);
+        deposit(&mut pool, &mut minnow, 1_000_000, 0);
+
+        pool.accrue(86_400).unwrap();
+        assert!(minnow.pending_rewards(&pool).unwrap() > 0);
+    }
+
+    #[test]
+    fn payouts_never_exceed_funded_rewards() {
+        let reward_rate = 7_919;
+        let mut pool = pool(reward_rate);
+        let mut users: Vec<UserStake> = (0..4).map(|_| stake()).collect();
+
+        deposit(&mut pool, &mut users[0], 3_333_333, 10);
+        deposit(&mut pool, &mut users[1], 17, 55);
+        deposit(&mut pool, &mut users[2], 999_999_999_999, 101);
+        deposit(&mut pool, &mut users[0], 1, 250);
+        deposit(&mut pool, &mut users[3], 42_424_242, 3_600);
+
+        let end = 86_400;
+        let paid: u64 = users.iter_mut().map(|user| withdraw_all(&mut pool, user, end)).sum();
+
+        // Rewards only accrue while something is staked, which starts at t=10
+        let funded = reward_rate * (end - 10) as u64;
+        assert!(paid <= funded);
+        // Truncation loses at most one unit per accrual and per settlement
+        assert!(funded - paid <= 18);
+    }
+
+    #[test]
+    fn boosted_weight_earns_proportionally_more() {
+        let mut pool = pool(1_000);
+        let mut flipper = stake();
+        let mut locker = stake();
+
+        deposit(&mut pool, &mut flipper, 1_000, 0);
+        deposit(&mut pool, &mut locker, 1_000, 0);
+        locker.settle(&pool).unwrap();
+        locker.set_weight(&mut pool, 3_000);
+
+        pool.accrue(100).unwrap();
+        assert_eq!(flipper.pending_rewards(&pool).unwrap(), 25_000);
+        assert_eq!(locker.pending_rewards(&pool).unwrap(), 75_000);
+    }
+
+    #[test]
+    fn expired_boosts_pay_out_from_the_lock_end() {
+        let mut pool = pool(1_000);
+        let mut plain = stake();
+        let mut locker = stake();
+        deposit(&mut pool, &mut plain, 1_000, 0);
+        deposit(&mut pool, &mut locker, 1_000, 0);
+        locker.set_weight(&mut pool, 4_000);
+        locker.lock_end_time = 50;
+
+        // Still locked, the boost is earned
+        pool.accrue(40).unwrap();
+        assert_eq!(locker.expired_boost_rewards(&pool, 1_000, 40).unwrap(), 0);
+
+        // 80_000 earned over 100s, 3/4 of it by the boost and half of that after the lock
+        pool.accrue(100).unwrap();
+        assert_eq!(locker.pending_rewards(&pool).unwrap(), 80_000);
+        assert_eq!(locker.expired_boost_rewards(&pool, 1_000, 100).unwrap(), 30_000);
+
+        // Once settled after the lock, everything the boost earns is stale
+        locker.settle(&pool).unwrap();
+        assert_eq!(locker.last_settle_time, 100);
+        pool.accrue(200).unwrap();
+        assert_eq!(locker.expired_boost_rewards(&pool, 1_000, 200).unwrap(), 60_000);
+    }
+
+    #[test]
+    fn empty_pool_does_not_accrue() {
+        let mut pool = pool(1_000);
+        pool.accrue(1_000).unwrap();
+        assert_eq!(pool.reward_per_token, 0);
+        assert_eq!(pool.last_update_time, 1_000);
+
+        let mut user = stake();
+        deposit(&mut pool, &mut user, 500, 1_000);
+        assert_eq!(withdraw_all(&mut pool, &mut user, 1_010), 10_000);
+    }
+
+    #[test]
+    fn halving_schedule_caps_each_epoch() {
+        let mut pool = scheduled_pool(EmissionSchedule {
+            start_time: 100,
+            end_time: 100 + 4 * 1_000,
+            epoch_duration: 1_000,
+            initial_epoch_emission: 8_000,
+            decay: EmissionDecay::Halving,
+            decay_param: 1,
+        });
+        let mut user = stake();
+
+        deposit(&mut pool, &mut user, 1_000, 0);
+        pool.accrue(1_100).unwrap();
+        assert_eq!(pool.total_distributed, 8_000);
+
+        // 4_000 + 2_000 + 1_000, then the schedule ends
+        pool.accrue(10_000).unwrap();
+        assert_eq!(pool.total_distributed, 15_000);
+        assert_eq!(withdraw_all(&mut pool, &mut user, 10_000), 15_000);
+    }
+
+    #[test]
+    fn emission_sums_match_the_epoch_budgets() {
+        for (decay, decay_param) in [
+            (EmissionDecay::Constant, 0),
+            (EmissionDecay::Halving, 3),
+            (EmissionDecay::Linear, 0),
+            (EmissionDecay::Linear, 700),
+        ] {
+            let schedule = EmissionSchedule {
+                start_time: 0,
+                end_time: i64::MAX,
+                epoch_duration: 1,
+                initial_epoch_emission: 10_000,
+                decay,
+                decay_param,
+            };
+            for (from, to) in [(0, 0), (0, 1), (2, 9), (5, 40), (13, 14), (30, 200)] {
+                let summed: u128 = (from..to).map(|epoch| schedule.epoch_emission(epoch) as u128).sum();
+                assert_eq!(schedule.emission_between(from, to), summed, "{:?} {} {}..{}", decay, decay_param, from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn long_gaps_accrue_without_visiting_each_epoch() {
+        // A century of one-second epochs, which a per-epoch loop could not get through
+        let mut secondly = scheduled_pool(EmissionSchedule {
+            start_time: 0,
+            end_time: i64::MAX,
+            epoch_duration: 1,
+            initial_epoch_emission: 3,
+            decay: EmissionDecay::Constant,
+            decay_param: 0,
+        });
+        let mut user = stake();
+        let century = 100 * 365 * 86_400;
+
+        deposit(&mut secondly, &mut user, 1_000, 0);
+        secondly.accrue(century).unwrap();
+        assert_eq!(secondly.total_distributed, 3 * century as u64);
+        assert_eq!(secondly.epoch, century as u64 - 1);
+        assert_eq!(secondly.epoch_emitted, 3);
+
+        // Halfway through an epoch the rest of its budget is still to come
+        let mut daily = pool(1_000);
+        deposit(&mut daily, &mut stake(), 1_000, 0);
+        daily.accrue(86_400 + 43_200).unwrap();
+        assert_eq!(daily.total_distributed, 129_600 * 1_000);
+        assert_eq!(daily.epoch, 1);
+        assert_eq!(daily.epoch_emitted, 43_200 * 1_000);
+    }
+
+    #[test]
+    fn accrual_stops_at_treasury_balance() {
+        let mut pool = pool(1_000);
+        pool.sync_treasury(25_000, 0);
+        let mut user = stake();
+
+        deposit(&mut pool, &mut user, 500, 0);
+        pool.accrue(100).unwrap();
+        assert_eq!(pool.total_distributed, 25_000);
+
+        // Paying out does not free up room until the treasury is refunded
+        pool.total_paid += withdraw_all(&mut pool, &mut user, 100);
+        pool.sync_treasury(0, 0);
+        deposit(&mut pool, &mut user, 500, 100);
+        pool.accrue(200).unwrap();
+        assert_eq!(user.pending_rewards(&pool).unwrap(), 0);
+
+        pool.sync_treasury(5_000, 0);
+        pool.accrue(300).unwrap();
+        assert_eq!(user.pending_rewards(&pool).unwrap(), 5_000);
+
+        // Funded reporter rewards are held back from emissions
+        pool.sync_treasury(14_000, 6_000);
+        pool.accrue(400).unwrap();
+        assert_eq!(user.pending_rewards(&pool).unwrap(), 8_000);
+        assert_eq!(pool.owed(), 8_000);
+    }
+
+    #[test]
+    fn penalties_raise_the_cap_only_when_credited() {
+        let mut pool = pool(1_000);
+        pool.sync_treasury(10_000, 0);
+
+        // Nobody is left to credit, the penalty is not promised to anyone
+        pool.distribute_penalty(500).unwrap();
+        assert_eq!((pool.total_distributed, pool.emission_cap), (0, 10_000));
+
+        let mut user = stake();
+        deposit(&mut pool, &mut user, 1_000, 0);
+        pool.distribute_penalty(500).unwrap();
+        assert_eq!((pool.total_distributed, pool.emission_cap), (500, 10_500));
+        assert_eq!(user.pending_rewards(&pool).unwrap(), 500);
+    }
+
+    #[test]
+    fn delegators_share_rewards_net_of_commission() {
+        let mut reporter = ReporterStats {
+            total_reports: 0,
+            successful_reports: 0,
+            total_stake: LAMPORTS_PER_WHOLE_SOL,
+            quick_flag_stake: 0,
+            reputation_score: 50,
+            last_report_time: 0,
+            reports_in_window: 0,
+            cooldown_end_time: 0,
+            token_balance: 0,
+            rewards_claimed: 0,
+            reward_points: 0,
+            points_epoch: 0,
+            delegated_stake: 9 * STAKE_TOKEN_UNIT,
+            delegation_shares: 9 * STAKE_TOKEN_UNIT,
+            commission_bps: 1_000,
+            delegation_reward_per_share: 0,
+        };
+        let mut backer = Delegation {
+            delegator: Pubkey::new_unique(),
+            reporter: Pubkey::new_unique(),
+            shares: 9 * STAKE_TOKEN_UNIT,
+            reward_per_share_paid: 0,
+            rewards_earned: 0,
+        };
+
+        // 1 SOL and 9 tokens weigh 1 and 3, so delegators earn 750 of 1_000 and 10% of that is commission
+        reporter.credit_rewards(1_000).unwrap();
+        backer.settle(&reporter).unwrap();
+        assert_eq!(backer.rewards_earned, 675);
+        assert_eq!(reporter.token_balance, 325);
+
+        // Slashing halves the pool, so shares are worth half and new ones cost double
+        reporter.delegated_stake = 4_500;
+        reporter.delegation_shares = 9_000;
+        assert_eq!(reporter.delegation_value(9_000), 4_500);
+        assert_eq!(reporter.delegation_shares_for(1_500).unwrap(), 3_000);
+        assert_eq!(reporter.delegation_shares_covering(1_001), 2_002);
+        assert_eq!(reporter.delegation_shares_covering(1_000_000), 9_000);
+    }
+
+    fn reporter() -> ReporterStats {
+        ReporterStats {
+            total_reports: 0,
+            successful_reports: 0,
+            total_stake: 0,
+            quick_flag_stake: 0,
+            reputation_score: 50,
+            last_report_time: 0,
+            reports_in_window: 0,
+            cooldown_end_time: 0,
+            token_balance: 0,
+            rewards_claimed: 0,
+            reward_points: 0,
+            points_epoch: 0,
+            delegated_stake: 0,
+            delegation_shares: 0,
+            commission_bps: 0,
+            delegation_reward_per_share: 0,
+        }
+    }
+
+    #[test]
+    fn slashes_split_once_between_reporter_and_delegators() {
+        let mut stats = reporter();
+        stats.delegated_stake = 1_000;
+        stats.delegation_shares = 1_000;
+        stats.commission_bps = 1_000;
+
+        // The reporter holds 200 of the shares and bears the 10% commission share of the slash
+        let (reporter_part, delegators_part, reporter_shares) = stats.slash_delegations(200, 100).unwrap();
+        assert_eq!((reporter_part, delegators_part), (10, 90));
+        assert_eq!(stats.delegated_stake, 900);
+        assert_eq!(stats.delegation_value(reporter_shares), 189);
+        assert_eq!(stats.delegation_value(stats.delegation_shares - reporter_shares), 710);
+
+        // Without a delegation of their own the delegators bear it all
+        let (reporter_part, delegators_part, reporter_shares) = stats.slash_delegations(0, 100).unwrap();
+        assert_eq!((reporter_part, delegators_part, reporter_shares), (0, 100, 0));
+        assert_eq!(stats.delegated_stake, 800);
+
+        // The delegators never lose more than they hold and the reporter covers the rest,
+        // but shares cannot leave the delegators with nothing while the reporter keeps some
+        let mut stats = reporter();
+        stats.delegated_stake = 1_000;
+        stats.delegation_shares = 1_000;
+        let (reporter_part, delegators_part, _) = stats.slash_delegations(200, 1_000).unwrap();
+        assert_eq!((reporter_part, delegators_part), (200, 800));
+        assert_eq!(stats.delegated_stake, 0);
+
+        let mut stats = reporter();
+        stats.delegated_stake = 1_000;
+        stats.delegation_shares = 1_000;
+        assert!(stats.slash_delegations(200, 900).is_err());
+        assert!(stats.slash_delegations(200, 1_001).is_err());
+    }
+
+    #[test]
+    fn forfeited_reporter_rewards_roll_over() {
+        let mut pool = ReporterRewardPool {
+            epoch: 0,
+            epoch_start: 0,
+            epoch_budget: 0,
+            epoch_points: 0,
+            closed_epochs: Vec::new(),
+            total_funded: 0,
+            total_claimed: 0,
+        };
+        let mut idle = reporter();
+        let mut active = reporter();
+
+        pool.start_next_epoch(1_000, 0).unwrap();
+        pool.add_points(&mut idle, 10).unwrap();
+        pool.add_points(&mut active, 30).unwrap();
+        pool.start_next_epoch(0, 1).unwrap();
+        pool.settle(&mut active).unwrap();
+        assert_eq!(active.token_balance, 750);
+        assert_eq!(pool.closed_epochs[0].unsettled_points, 10);
+
+        // Keep closing epochs until the idle reporter's epoch is dropped
+        for now in 2..=MAX_CLOSED_REWARD_EPOCHS as i64 {
+            pool.add_points(&mut active, 1).unwrap();
+            pool.start_next_epoch(0, now).unwrap();
+        }
+        assert_eq!(pool.closed_epochs.len(), MAX_CLOSED_REWARD_EPOCHS);
+        assert_eq!(pool.try_to_vec().unwrap().len(), REPORTER_REWARD_POOL_SPACE);
+        pool.add_points(&mut active, 1).unwrap();
+        pool.start_next_epoch(100, MAX_CLOSED_REWARD_EPOCHS as i64 + 1).unwrap();
+
+        // The idle reporter's 250 go to the next epoch instead of staying reserved
+        assert_eq!(pool.epoch_budget, 250 + 100);
+        pool.settle(&mut idle).unwrap();
+        assert_eq!(idle.token_balance, 0);
+        assert_eq!(pool.total_funded, 1_100);
+    }
+
+    #[test]
+    fn aggregate_score_survives_large_weights() {
+        let mut stats = AddressStats {
+            address: Pubkey::new_unique(),
+            total_reports: 0,
+            risk_scores: Vec::new(),
+            risk_score_times: Vec::new(),
+            total_stake: 0,
+            last_update: 0,
+            last_report_time: 0,
+            weighted_risk_score: 0,
+            total_vote_weight: 0,
+            reporters: Vec::new(),
+            total_confidence: 0,
+        };
+
+        for score in [90u8, 70] {
+            stats.weighted_risk_score += score as u128 * (u64::MAX / 4) as u128;
+            stats.total_vote_weight += u64::MAX / 4;
+        }
+        assert_eq!(stats.aggregate_score(), 80);
+    }
+
+    #[test]
+    fn removing_a_risk_score_takes_only_the_reports_entry() {
+        let mut stats = AddressStats {
+            address: Pubkey::new_unique(),
+            total_reports: 0,
+            risk_scores: Vec::new(),
+            risk_score_times: Vec::new(),
+            total_stake: 0,
+            last_update: 0,
+            last_report_time: 0,
+            weighted_risk_score: 0,
+            total_vote_weight: 0,
+            reporters: Vec::new(),
+            total_confidence: 0,
+        };
+        stats.push_risk_score(50, 10);
+        stats.push_risk_score(70, 20);
+        stats.push_risk_score(50, 30);
+
+        stats.remove_risk_score(50, 30);
+        assert_eq!(stats.risk_scores, vec![50, 70]);
+        assert_eq!(stats.risk_score_times, vec![10, 20]);
+
+        // Once evicted, the entry is gone and nothing else is taken in its place
+        for time in 100..100 + MAX_RECENT_RISK_SCORES as i64 {
+            stats.push_risk_score(50, time);
+        }
+        stats.remove_risk_score(50, 10);
+        assert_eq!(stats.risk_scores.len(), MAX_RECENT_RISK_SCORES);
+        assert_eq!(stats.risk_score_times.first(), Some(&100));
+    }
+
+    #[test]
+    fn largest_report_fits_its_space() {
+        let report = AddressReport {
+            reporter: Pubkey::new_unique(),
+            reported_address: Pubkey::new_unique(),
+            risk_score: 100,
+            stake_amount: u64::MAX,
+            timestamp: 0,
+            description: "d".repeat(MAX_DESCRIPTION_LEN),
+            vote_weight: u64::MAX,
+            last_update_time: 0,
+            time_lock_end: 0,
+            risk_assessment: RiskAssessment {
+                base_score: 100,
+                risk_types: vec![RiskType::Unknown; MAX_RISK_TYPES],
+                confidence_score: 100,
+                evidence_count: u32::MAX,
+                last_update: 0,
+            },
+            risk_metrics: RiskMetrics {
+                transaction_volume: u64::MAX,
+                unique_interactions: u32::MAX,
+                age_of_account: 0,
+                suspicious_patterns: vec!["p".repeat(MAX_PATTERN_LEN); MAX_SUSPICIOUS_PATTERNS],
+            },
+            verified: true,
+        };
+
+        assert_eq!(report.try_to_vec().unwrap().len(), ADDRESS_REPORT_SPACE);
+    }
+
+    #[test]
+    fn full_stats_and_history_fit_their_space() {
+        let mut stats = AddressStats {
+            address: Pubkey::new_unique(),
+            total_reports: u32::MAX,
+            risk_scores: Vec::new(),
+            risk_score_times: Vec::new(),
+            total_stake: u64::MAX,
+            last_update: 0,
+            last_report_time: 0,
+            weighted_risk_score: u128::MAX,
+            total_vote_weight: u64::MAX,
+            reporters: Vec::new(),
+            total_confidence: u64::MAX,
+        };
+        for score in 0..=MAX_RECENT_RISK_SCORES as u8 {
+            stats.push_risk_score(score, score as i64);
+            stats.add_reporter(Pubkey::new_unique());
+        }
+        for _ in 0..MAX_TRACKED_REPORTERS {
+            stats.add_reporter(Pubkey::new_unique());
+        }
+        assert_eq!(stats.risk_scores.first(), Some(&1));
+        assert_eq!(stats.reporters.len(), MAX_TRACKED_REPORTERS);
+        assert_eq!(stats.try_to_vec().unwrap().len(), ADDRESS_STATS_SPACE);
+
+        let mut history = ReportHistory::new(Pubkey::new_unique());
+        history.blacklist_reason = "r".repeat(MAX_BLACKLIST_REASON_LEN);
+        for timestamp in 0..=MAX_HISTORY_REPORTS.max(MAX_BLACKLIST_LOG) as i64 {
+            history.push_report(HistoricalReport {
+                timestamp,
+                risk_score: 100,
+                reporter: Pubkey::new_unique(),
+                description: "d".repeat(MAX_DESCRIPTION_LEN),
+            });
+            history.push_listing_change(BlacklistRecord {
+                action: BlacklistAction::Blacklist,
+                status: ListingStatus::Block,
+                authority: Pubkey::new_unique(),
+                reason: "r".repeat(MAX_BLACKLIST_REASON_LEN),
+                timestamp,
+                expiry: 0,
+            });
+        }
+        assert_eq!(history.reports.len(), MAX_HISTORY_REPORTS);
+        assert_eq!(history.blacklist_log.last().map(|record| record.timestamp), Some(MAX_BLACKLIST_LOG as i64));
+        assert_eq!(history.try_to_vec().unwrap().len(), REPORT_HISTORY_SPACE);
+    }
+}